@@ -0,0 +1,54 @@
+/// Result of converting a TON raw amount into SPL base units.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Normalized {
+    pub amount_sol: u64,
+    /// TON raw units that cannot be represented at SPL precision (truncated).
+    pub dust_raw: u128,
+}
+
+/// Map `amount_raw` at `decimals_ton` precision to `decimals_sol` precision.
+///
+/// Scaling up fails on overflow; scaling down truncates and reports the
+/// remainder as dust. The result must fit the SPL `u64` amount.
+pub fn ton_to_sol(amount_raw: u128, decimals_ton: u8, decimals_sol: u8) -> Result<Normalized, String> {
+    let (scaled, dust_raw) = if decimals_sol >= decimals_ton {
+        let f = pow10(decimals_sol - decimals_ton)?;
+        let v = amount_raw
+            .checked_mul(f)
+            .ok_or_else(|| format!("amount {amount_raw} overflows u128 when scaled by 10^{}", decimals_sol - decimals_ton))?;
+        (v, 0)
+    } else {
+        let f = pow10(decimals_ton - decimals_sol)?;
+        (amount_raw / f, amount_raw % f)
+    };
+    let amount_sol = u64::try_from(scaled).map_err(|_| format!("amount {scaled} (SPL units) overflows u64"))?;
+    Ok(Normalized { amount_sol, dust_raw })
+}
+
+fn pow10(exp: u8) -> Result<u128, String> {
+    10u128.checked_pow(exp as u32).ok_or_else(|| format!("10^{exp} overflows u128"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scales_down_with_dust() {
+        assert_eq!(ton_to_sol(1_500_000_123, 9, 6), Ok(Normalized { amount_sol: 1_500_000, dust_raw: 123 }));
+        assert_eq!(ton_to_sol(1_500_000_000, 9, 6), Ok(Normalized { amount_sol: 1_500_000, dust_raw: 0 }));
+    }
+
+    #[test]
+    fn scales_up_and_same_precision() {
+        assert_eq!(ton_to_sol(15, 6, 9).unwrap().amount_sol, 15_000);
+        assert_eq!(ton_to_sol(42, 9, 9).unwrap().amount_sol, 42);
+    }
+
+    #[test]
+    fn rejects_overflow() {
+        assert!(ton_to_sol(u64::MAX as u128 + 1, 9, 9).is_err());
+        assert!(ton_to_sol(u64::MAX as u128, 0, 1).is_err());
+        assert!(ton_to_sol(u128::MAX, 0, 38).is_err());
+    }
+}
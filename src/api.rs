@@ -1,69 +1,1464 @@
 use axum::{
-    extract::{Query, State},
-    response::sse::{Event, Sse},
-    routing::get,
-    Json, Router,
+    extract::{Path, Query, State},
+    http::{header, HeaderMap, Method, StatusCode},
+    extract::ws::WebSocketUpgrade,
+    response::{
+        sse::{Event, Sse},
+        IntoResponse, Response,
+    },
+    routing::{MethodFilter, MethodRouter},
+    Extension, Json, Router,
 };
+use base64::Engine;
 use serde::Deserialize;
 use sqlx::SqlitePool;
-use std::{convert::Infallible, time::Duration};
-use tokio_stream::StreamExt as _;
+use std::{collections::BTreeMap, convert::Infallible, sync::Arc};
+use tokio::sync::broadcast::error::RecvError;
+use tokio_util::sync::CancellationToken;
+use utoipa::OpenApi as _;
+
+use crate::auth::{Identity, Scope};
+use crate::config::Cfg;
+use crate::errors::ApiError;
+use crate::events::{EventBus, EventRecord, RelayerEvent};
+use crate::supervisor::Supervisor;
+use crate::ton::address::TonAddress;
+use schema::BearerAuth;
 
 #[derive(Clone)]
 pub struct AppState {
+    pub cfg: Arc<Cfg>,
     pub db: SqlitePool,
-    pub tx: tokio::sync::broadcast::Sender<String>,
+    pub bus: EventBus,
+    pub sup: Supervisor,
+    pub deps: crate::health::DependencyCache,
+    pub shutdown: CancellationToken,
 }
 
-pub async fn serve(bind: String, db: SqlitePool, tx: tokio::sync::broadcast::Sender<String>) -> anyhow::Result<()> {
-    let state = AppState { db, tx };
-
-    let app = Router::new()
-        .route("/health", get(health))
-        .route("/burns", get(burns))
-        .route("/attestations", get(attestations))
-        .route("/events", get(events))
-        .with_state(state);
+/// Serves until `shutdown` is cancelled, then stops accepting connections and
+/// waits for in-flight requests (SSE streams end with a `shutdown` event).
+pub async fn serve(
+    cfg: Cfg,
+    db: SqlitePool,
+    bus: EventBus,
+    sup: Supervisor,
+    shutdown: CancellationToken,
+) -> anyhow::Result<()> {
+    let bind = cfg.api_bind;
+    if cfg.admin_tokens.is_empty() {
+        tracing::warn!("no admin tokens configured; /admin endpoints will refuse every call");
+    }
+    let state = AppState { cfg: Arc::new(cfg), db, bus, sup, deps: Default::default(), shutdown: shutdown.clone() };
+    let app = router(state);
 
     tracing::info!("HTTP API listening on http://{bind}");
-    axum::Server::bind(&bind.parse()?)
-        .serve(app.into_make_service())
+    let listener = tokio::net::TcpListener::bind(bind).await?;
+    axum::serve(listener, app.into_make_service_with_connect_info::<std::net::SocketAddr>())
+        .with_graceful_shutdown(shutdown.cancelled_owned())
         .await?;
     Ok(())
 }
 
-async fn health() -> Json<serde_json::Value> {
-    Json(serde_json::json!({"ok": true}))
+/// Guard `m` with a bearer token of at least `scope`; see [`crate::auth::require`].
+fn scoped(st: &AppState, scope: Scope, m: MethodRouter<AppState>) -> MethodRouter<AppState> {
+    m.route_layer(axum::middleware::from_fn_with_state(
+        st.clone(),
+        move |s: State<AppState>, req: axum::extract::Request, next: axum::middleware::Next| {
+            crate::auth::require(s, scope, req, next)
+        },
+    ))
+}
+
+/// Who may call an endpoint.
+#[derive(Debug, Clone, Copy)]
+enum Access {
+    /// Anyone, within the client's rate limit.
+    Public,
+    /// Anyone, without a limit: the liveness probe, which must not fail
+    /// because other clients spent the budget.
+    Probe,
+    /// Bearer token of at least this scope; authenticated calls are not rate limited.
+    Token(Scope),
+}
+
+struct Endpoint {
+    /// Already part of `handler`; kept for the OpenAPI test.
+    #[cfg_attr(not(test), allow(dead_code))]
+    method: Method,
+    /// axum syntax, `:param`.
+    path: &'static str,
+    access: Access,
+    handler: MethodRouter<AppState>,
+}
+
+fn endpoint<H, T>(method: Method, path: &'static str, access: Access, handler: H) -> Endpoint
+where
+    H: axum::handler::Handler<T, AppState>,
+    T: 'static,
+{
+    let filter = MethodFilter::try_from(method.clone()).expect("supported method");
+    Endpoint { method, path, access, handler: axum::routing::on(filter, handler) }
+}
+
+/// Every endpoint. [`router`] is built from this list and the OpenAPI test
+/// checks it against [`ApiDoc`], so a route cannot be added to one only.
+fn endpoints() -> Vec<Endpoint> {
+    use Access::*;
+    use Method as M;
+    vec![
+        endpoint(M::GET, "/config/hash", Public, config_hash),
+        endpoint(M::GET, "/routes", Public, routes),
+        endpoint(M::GET, "/openapi.json", Public, openapi),
+        endpoint(M::GET, "/docs", Public, docs),
+        endpoint(M::GET, "/burns", Public, burns),
+        endpoint(M::GET, "/burns/:tx_hash", Public, burn_by_hash),
+        endpoint(M::GET, "/attestations", Public, attestations),
+        endpoint(M::GET, "/attestations/:hash", Public, attestation_by_hash),
+        endpoint(M::GET, "/transfers/:src_tx", Public, transfer),
+        endpoint(M::GET, "/stats", Public, stats),
+        endpoint(M::GET, "/events", Public, events),
+        endpoint(M::GET, "/ws", Public, events_ws),
+        endpoint(M::GET, "/health", Public, health),
+        endpoint(M::GET, "/health/live", Probe, live),
+        endpoint(M::GET, "/health/ready", Public, ready),
+        endpoint(M::GET, "/metrics", Public, metrics),
+        endpoint(M::GET, "/admin/quarantine", Token(Scope::Read), quarantine_list),
+        endpoint(M::POST, "/admin/quarantine/:id/release", Token(Scope::Operator), quarantine_release),
+        endpoint(M::POST, "/admin/quarantine/:id/discard", Token(Scope::Operator), quarantine_discard),
+        endpoint(M::GET, "/admin/webhooks", Token(Scope::Read), webhook_list),
+        endpoint(M::POST, "/admin/webhooks", Token(Scope::Admin), webhook_create),
+        endpoint(M::DELETE, "/admin/webhooks/:id", Token(Scope::Admin), webhook_delete),
+        endpoint(M::GET, "/admin/webhooks/dead_letters", Token(Scope::Read), dead_letter_list),
+        endpoint(M::POST, "/admin/webhooks/dead_letters/:id/replay", Token(Scope::Operator), dead_letter_replay),
+        endpoint(M::GET, "/admin/pause", Token(Scope::Read), pause_status),
+        endpoint(M::POST, "/admin/pause", Token(Scope::Operator), pause),
+        endpoint(M::POST, "/admin/resume", Token(Scope::Operator), resume),
+        endpoint(M::POST, "/admin/attestations/:hash/resubmit", Token(Scope::Operator), attestation_resubmit),
+        endpoint(M::POST, "/admin/burns/:tx_hash/rebuild", Token(Scope::Operator), burn_rebuild),
+        endpoint(M::GET, "/admin/audit", Token(Scope::Admin), audit_list),
+    ]
+}
+
+pub fn router(state: AppState) -> Router {
+    let st = &state;
+    let cfg = st.cfg.clone();
+    let limiter = cfg
+        .api_rate_limit
+        .map(|limit| Arc::new(crate::ratelimit::Limiter::new(limit, cfg.api_client_ip_header.clone())));
+    let mut paths: BTreeMap<&str, MethodRouter<AppState>> = BTreeMap::new();
+    for e in endpoints() {
+        let handler = match (e.access, &limiter) {
+            (Access::Token(scope), _) => scoped(st, scope, e.handler),
+            (Access::Public, Some(limiter)) => e
+                .handler
+                .route_layer(axum::middleware::from_fn_with_state(limiter.clone(), crate::ratelimit::limit)),
+            (Access::Public | Access::Probe, _) => e.handler,
+        };
+        let merged = match paths.remove(e.path) {
+            Some(other) => other.merge(handler),
+            None => handler,
+        };
+        paths.insert(e.path, merged);
+    }
+
+    let app = paths
+        .into_iter()
+        .fold(Router::new(), |app, (path, handler)| app.route(path, handler))
+        // Innermost first. Timeouts cover producing the response head, so
+        // /events and /ws streams are not cut off; compression skips SSE and
+        // wraps request_context so error bodies are rewritten uncompressed.
+        .layer(axum::extract::DefaultBodyLimit::max(cfg.api_max_body_bytes))
+        .layer(tower_http::timeout::TimeoutLayer::new(cfg.api_request_timeout))
+        .layer(
+            tower_http::trace::TraceLayer::new_for_http()
+                .make_span_with(|req: &axum::extract::Request| {
+                    let id = req.headers().get(REQUEST_ID_HEADER).and_then(|v| v.to_str().ok()).unwrap_or("-");
+                    tracing::info_span!(
+                        "http",
+                        request_id = %id,
+                        method = %req.method(),
+                        path = %req.uri().path(),
+                        status = tracing::field::Empty,
+                        latency_ms = tracing::field::Empty,
+                    )
+                })
+                .on_request(())
+                .on_response(|res: &axum::http::Response<_>, latency: std::time::Duration, span: &tracing::Span| {
+                    let (status, latency_ms) = (res.status().as_u16(), latency.as_millis() as u64);
+                    span.record("status", status);
+                    span.record("latency_ms", latency_ms);
+                    tracing::debug!(status, latency_ms, "response");
+                })
+                .on_body_chunk(())
+                .on_eos(())
+                .on_failure(()),
+        )
+        .layer(axum::middleware::from_fn(request_context))
+        .layer(tower_http::compression::CompressionLayer::new())
+        .with_state(state);
+
+    if cfg.api_cors_origins.is_empty() {
+        return app;
+    }
+    let origins = cfg.api_cors_origins.iter().filter_map(|o| o.parse().ok()).collect::<Vec<header::HeaderValue>>();
+    app.layer(
+        tower_http::cors::CorsLayer::new()
+            .allow_origin(origins)
+            .allow_methods([axum::http::Method::GET, axum::http::Method::POST, axum::http::Method::DELETE])
+            .allow_headers([
+                header::AUTHORIZATION,
+                header::CONTENT_TYPE,
+                header::HeaderName::from_static("last-event-id"),
+                header::HeaderName::from_static(REQUEST_ID_HEADER),
+            ])
+            .expose_headers([header::HeaderName::from_static(REQUEST_ID_HEADER), header::RETRY_AFTER])
+            .max_age(std::time::Duration::from_secs(3600)),
+    )
+}
+
+const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// Give every request an id (the caller's `X-Request-Id` when it is sane),
+/// which the trace span and error bodies quote, echo it in the response, and
+/// turn plain-text rejections from axum and the layers (bad query, unknown
+/// path, timeout, ...) into the same JSON error body the handlers return.
+async fn request_context(mut req: axum::extract::Request, next: axum::middleware::Next) -> Response {
+    let id = req
+        .headers()
+        .get(REQUEST_ID_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .filter(|v| !v.is_empty() && v.len() <= 64 && v.bytes().all(|b| b.is_ascii_graphic()))
+        .map(String::from)
+        .unwrap_or_else(new_request_id);
+    let value = header::HeaderValue::from_str(&id).expect("request ids are visible ASCII");
+    req.headers_mut().insert(REQUEST_ID_HEADER, value.clone());
+    let handle = async move {
+        let res = next.run(req).await;
+        let is_json = res
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .is_some_and(|v| v.as_bytes().starts_with(b"application/json"));
+        if !(res.status().is_client_error() || res.status().is_server_error()) || is_json {
+            return res;
+        }
+        let status = res.status();
+        let text = axum::body::to_bytes(res.into_body(), 4096).await.unwrap_or_default();
+        let text = String::from_utf8_lossy(&text);
+        let msg = match text.trim() {
+            "" => status.canonical_reason().unwrap_or("error").to_string(),
+            t => t.to_string(),
+        };
+        ApiError::new(status, msg).into_response()
+    };
+    let mut res = crate::errors::REQUEST_ID.scope(id, handle).await;
+    res.headers_mut().insert(REQUEST_ID_HEADER, value);
+    res
+}
+
+fn new_request_id() -> String {
+    let mut b = [0u8; 8];
+    // Only used for correlation; a clock-based fallback is good enough.
+    if getrandom::getrandom(&mut b).is_err() {
+        b = (std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap_or_default().as_nanos() as u64)
+            .to_be_bytes();
+    }
+    hex::encode(b)
+}
+
+/// Liveness: 503 only when a worker has been given up on and the process
+/// needs a restart.
+#[utoipa::path(
+    get, path = "/health/live", tag = "health",
+    responses(
+        (status = 200, description = "Every worker is alive", body = Object),
+        (status = 503, description = "A worker has been given up on", body = Object),
+    )
+)]
+async fn live(State(st): State<AppState>) -> (StatusCode, Json<serde_json::Value>) {
+    let (alive, workers) = crate::health::liveness(&st.sup);
+    let code = if alive { StatusCode::OK } else { StatusCode::SERVICE_UNAVAILABLE };
+    (code, Json(serde_json::json!({ "alive": alive, "workers": workers })))
+}
+
+/// Readiness: 503 unless every dependency needed to relay is reachable and
+/// current. `/health` is kept as an alias.
+#[utoipa::path(
+    get, path = "/health/ready", tag = "health",
+    responses(
+        (status = 200, description = "Ready to relay; per-check details", body = Object),
+        (status = 503, description = "A check failed; per-check details", body = Object),
+    )
+)]
+async fn ready(State(st): State<AppState>) -> (StatusCode, Json<crate::health::Readiness>) {
+    let r = crate::health::readiness(&st.cfg, &st.db, &st.sup, &st.deps, st.shutdown.is_cancelled()).await;
+    let code = if r.ready { StatusCode::OK } else { StatusCode::SERVICE_UNAVAILABLE };
+    (code, Json(r))
+}
+
+/// Alias of `/health/ready`.
+#[utoipa::path(
+    get, path = "/health", tag = "health",
+    responses(
+        (status = 200, description = "Ready to relay; per-check details", body = Object),
+        (status = 503, description = "A check failed; per-check details", body = Object),
+    )
+)]
+async fn health(st: State<AppState>) -> (StatusCode, Json<crate::health::Readiness>) {
+    ready(st).await
+}
+
+/// This API's OpenAPI 3 document, generated from the handlers.
+#[utoipa::path(
+    get, path = "/openapi.json", tag = "config",
+    responses((status = 200, description = "OpenAPI 3 document", body = Object))
+)]
+async fn openapi() -> Json<utoipa::openapi::OpenApi> {
+    Json(ApiDoc::openapi())
+}
+
+/// Browsable reference for `/openapi.json`. The page is self-contained: it
+/// loads nothing from third parties, and its CSP keeps it that way.
+#[utoipa::path(
+    get, path = "/docs", tag = "config",
+    responses((status = 200, description = "HTML page", body = String, content_type = "text/html"))
+)]
+async fn docs() -> impl IntoResponse {
+    (
+        [(
+            header::CONTENT_SECURITY_POLICY,
+            "default-src 'none'; connect-src 'self'; script-src 'unsafe-inline'; style-src 'unsafe-inline'",
+        )],
+        axum::response::Html(DOCS_HTML),
+    )
 }
 
-#[derive(Deserialize)]
-struct ListQ { limit: Option<i64> }
+const DOCS_HTML: &str = r#"<!doctype html>
+<html>
+  <head>
+    <title>bridge-relayer API</title>
+    <meta charset="utf-8" />
+    <meta name="viewport" content="width=device-width, initial-scale=1" />
+    <style>
+      body { font: 14px/1.5 system-ui, sans-serif; max-width: 960px; margin: 2em auto; padding: 0 1em; color: #222; }
+      details { border: 1px solid #ddd; border-radius: 4px; margin: .4em 0; padding: .3em .6em; }
+      summary { cursor: pointer; }
+      code, pre { font: 13px ui-monospace, monospace; }
+      pre { background: #f6f6f6; padding: .6em; overflow: auto; }
+      .m { display: inline-block; width: 4.5em; font-weight: bold; text-transform: uppercase; }
+      .lock { color: #a60; }
+    </style>
+  </head>
+  <body>
+    <h1 id="title">bridge-relayer API</h1>
+    <p>Machine-readable: <a href="openapi.json">openapi.json</a></p>
+    <div id="ops">Loading&hellip;</div>
+    <script>
+      const el = (tag, text, cls) => {
+        const e = document.createElement(tag);
+        if (text !== undefined) e.textContent = text;
+        if (cls) e.className = cls;
+        return e;
+      };
+      const json = v => el("pre", JSON.stringify(v, null, 2));
+      fetch("openapi.json").then(r => r.json()).then(doc => {
+        document.getElementById("title").textContent = `${doc.info.title} ${doc.info.version}`;
+        const root = document.getElementById("ops");
+        root.textContent = "";
+        const byTag = {};
+        for (const [path, item] of Object.entries(doc.paths)) {
+          for (const [method, op] of Object.entries(item)) {
+            for (const tag of op.tags || ["other"]) (byTag[tag] ||= []).push([method, path, op]);
+          }
+        }
+        for (const [tag, ops] of Object.entries(byTag).sort()) {
+          root.append(el("h2", tag));
+          for (const [method, path, op] of ops) {
+            const d = el("details"), s = el("summary");
+            s.append(el("span", method, "m"), el("code", path), " ", op.summary || "");
+            if (op.security) s.append(" ", el("span", "\u{1F512} token", "lock"));
+            d.append(s);
+            if (op.description) d.append(el("p", op.description));
+            if (op.parameters) d.append(el("h4", "Parameters"), json(op.parameters));
+            if (op.requestBody) d.append(el("h4", "Request body"), json(op.requestBody));
+            d.append(el("h4", "Responses"), json(op.responses));
+            root.append(d);
+          }
+        }
+        if (doc.components) {
+          const d = el("details");
+          d.append(el("summary", "Schemas"), json(doc.components));
+          root.append(el("h2", "components"), d);
+        }
+      }).catch(e => { document.getElementById("ops").textContent = `cannot load openapi.json: ${e}`; });
+    </script>
+  </body>
+</html>
+"#;
+
+/// Prometheus scrape endpoint. Queue depth is read from the DB at scrape time.
+#[utoipa::path(
+    get, path = "/metrics", tag = "health",
+    responses((status = 200, description = "Prometheus text format", body = String, content_type = "text/plain"))
+)]
+async fn metrics(State(st): State<AppState>) -> impl IntoResponse {
+    let m = &crate::metrics::METRICS;
+    match crate::repo::count_pending_attestations(&st.db).await {
+        Ok(n) => m.submission_queue_depth.set(n),
+        Err(e) => tracing::warn!("metrics: cannot count pending attestations: {e}"),
+    }
+    ([(header::CONTENT_TYPE, "text/plain; version=0.0.4")], m.render())
+}
+
+/// `cfg_hash` together with its preimage so operators and the on-chain
+/// program can recompute it independently.
+#[utoipa::path(
+    get, path = "/config/hash", tag = "config",
+    responses((status = 200, description = "cfg_hash with its Borsh preimage and a decoded view", body = Object))
+)]
+async fn config_hash(State(st): State<AppState>) -> Json<serde_json::Value> {
+    let p = st.cfg.cfg_preimage();
+    let borsh = borsh::to_vec(&p).unwrap_or_default();
+    let testnet = st.cfg.ton_testnet();
+    let routes: Vec<_> = p
+        .routes
+        .iter()
+        .map(|r| {
+            serde_json::json!({
+                "jetton_minter_ton": TonAddress::from_wc_hash36(&r.jetton_minter_ton).to_friendly(true, testnet),
+                "decimals_ton": r.decimals_ton,
+                "sol_mint": bs58::encode(r.sol_mint).into_string(),
+                "decimals_sol": r.decimals_sol,
+            })
+        })
+        .collect();
+    Json(serde_json::json!({
+        "cfg_hash_hex": hex::encode(st.cfg.cfg_hash),
+        "domain": crate::attestations::CFG_DOMAIN,
+        "preimage_borsh_b64": base64::engine::general_purpose::STANDARD.encode(&borsh),
+        "preimage": {
+            "ver": p.ver,
+            "src_chain": p.src_chain,
+            "dst_chain": p.dst_chain,
+            "ton_global_id": p.ton_global_id,
+            "sol_cluster": p.sol_cluster,
+            "ton_bridge": TonAddress::from_wc_hash36(&p.ton_bridge).to_friendly(true, testnet),
+            "sol_bridge_program": bs58::encode(p.sol_bridge_program).into_string(),
+            "routes": routes,
+            "signers": p.signers.iter().map(hex::encode).collect::<Vec<_>>(),
+            "threshold": p.threshold,
+        },
+    }))
+}
+
+#[utoipa::path(
+    get, path = "/routes", tag = "config",
+    responses((status = 200, description = "Configured routes", body = Object))
+)]
+async fn routes(State(st): State<AppState>) -> Json<serde_json::Value> {
+    let items: Vec<_> = st.cfg.routes.iter().collect();
+    Json(serde_json::json!({ "items": items }))
+}
+
+/// Query parameters shared by `/burns` and `/attestations`. Time bounds are
+/// unix seconds (`from` inclusive, `to` exclusive); amounts are inclusive.
+#[derive(Deserialize, utoipa::IntoParams)]
+#[into_params(parameter_in = Query)]
+struct ListQ {
+    /// 1 to 500, default 50.
+    limit: Option<i64>,
+    before_id: Option<i64>,
+    after_id: Option<i64>,
+    /// TON address, any format.
+    owner: Option<String>,
+    /// TON address, any format.
+    jetton_master: Option<String>,
+    from: Option<i64>,
+    to: Option<i64>,
+    /// Raw units, decimal.
+    min_amount: Option<String>,
+    /// Raw units, decimal.
+    max_amount: Option<String>,
+    /// Attestations only.
+    status: Option<String>,
+    /// Attestations only.
+    kind: Option<String>,
+    /// Attestations only.
+    src_tx: Option<String>,
+}
 
-async fn burns(State(st): State<AppState>, Query(q): Query<ListQ>) -> Json<serde_json::Value> {
+impl ListQ {
+    fn page(&self) -> Result<crate::repo::Page, ApiError> {
+        if self.before_id.is_some() && self.after_id.is_some() {
+            return Err(api_err(StatusCode::BAD_REQUEST, "before_id and after_id are mutually exclusive"));
+        }
+        Ok(crate::repo::Page {
+            before_id: self.before_id,
+            after_id: self.after_id,
+            limit: self.limit.unwrap_or(50).clamp(1, 500),
+        })
+    }
+
+    fn address(key: &str, v: &Option<String>) -> Result<Option<String>, ApiError> {
+        v.as_deref()
+            .map(|s| s.parse::<TonAddress>().map(|a| a.to_raw()))
+            .transpose()
+            .map_err(|e| api_err(StatusCode::BAD_REQUEST, format!("{key}: {e}")))
+    }
+
+    fn amount(key: &str, v: &Option<String>) -> Result<Option<u128>, ApiError> {
+        v.as_deref()
+            .map(|s| s.parse::<u128>())
+            .transpose()
+            .map_err(|e| api_err(StatusCode::BAD_REQUEST, format!("{key}: {e}")))
+    }
+}
+
+/// Wrap a page of rows with the ids to request the neighbouring pages.
+fn paged<T: serde::Serialize>(items: Vec<T>, id: impl Fn(&T) -> i64, page: crate::repo::Page) -> serde_json::Value {
+    let full = items.len() as i64 == page.limit;
+    let (first, last) = (items.first().map(&id), items.last().map(&id));
+    let (next_before_id, prev_after_id) = if page.after_id.is_some() {
+        (last, first.filter(|_| full))
+    } else {
+        (last.filter(|_| full), first.filter(|_| page.before_id.is_some()))
+    };
+    serde_json::json!({ "items": items, "next_before_id": next_before_id, "prev_after_id": prev_after_id })
+}
+
+/// Burns newest first. Filters: `owner`, `jetton_master` (raw or friendly),
+/// `from`/`to` on the TON transaction time, `min_amount`/`max_amount` in raw units.
+#[utoipa::path(
+    get, path = "/burns", tag = "burns", params(ListQ),
+    responses(
+        (status = 200, body = schema::BurnPage),
+        (status = 400, description = "Invalid filter or page", body = schema::Error),
+    )
+)]
+async fn burns(State(st): State<AppState>, Query(q): Query<ListQ>) -> ApiResult {
+    let page = q.page()?;
+    let f = crate::repo::BurnFilter {
+        owner: ListQ::address("owner", &q.owner)?,
+        jetton_master: ListQ::address("jetton_master", &q.jetton_master)?,
+        from_ts: q.from,
+        to_ts: q.to,
+        min_amount: ListQ::amount("min_amount", &q.min_amount)?,
+        max_amount: ListQ::amount("max_amount", &q.max_amount)?,
+    };
+    let rows = crate::repo::list_burns(&st.db, &f, page).await?;
+    Ok(Json(paged(rows, |r| r.id, page)))
+}
+
+/// Attestations newest first. Adds `status`, `kind` and `src_tx` to the burn
+/// filters; `from`/`to` apply to `created_at` and amounts to `amount_dst`.
+#[utoipa::path(
+    get, path = "/attestations", tag = "attestations", params(ListQ),
+    responses(
+        (status = 200, body = schema::AttestationPage),
+        (status = 400, description = "Invalid filter or page", body = schema::Error),
+    )
+)]
+async fn attestations(State(st): State<AppState>, Query(q): Query<ListQ>) -> ApiResult {
+    let page = q.page()?;
+    let f = crate::repo::AttFilter {
+        kind: q.kind.clone(),
+        status: q.status.clone(),
+        src_tx_hash_b64: q.src_tx.clone(),
+        owner: ListQ::address("owner", &q.owner)?,
+        jetton_master: ListQ::address("jetton_master", &q.jetton_master)?,
+        from_ts: q.from,
+        to_ts: q.to,
+        min_amount: ListQ::amount("min_amount", &q.min_amount)?,
+        max_amount: ListQ::amount("max_amount", &q.max_amount)?,
+    };
+    let rows = crate::repo::list_attestations(&st.db, &f, page).await?;
+    let testnet = st.cfg.ton_testnet();
+    let mut body = paged(rows.iter().collect(), |r| r.id, page);
+    for (item, row) in body["items"].as_array_mut().into_iter().flatten().zip(&rows) {
+        item["payload"] = crate::attestations::payload_json(&row.payload_borsh, testnet);
+    }
+    Ok(Json(body))
+}
+
+/// TON tx hash from a path segment: hex, base64url, or (percent-encoded)
+/// standard base64. Returned in the standard base64 form stored in the DB.
+fn parse_tx_hash(s: &str) -> Result<String, ApiError> {
+    use base64::engine::general_purpose::{STANDARD, URL_SAFE, URL_SAFE_NO_PAD};
+    let bytes = if s.len() == 64 && s.bytes().all(|b| b.is_ascii_hexdigit()) {
+        hex::decode(s).ok()
+    } else {
+        STANDARD.decode(s).or_else(|_| URL_SAFE.decode(s)).or_else(|_| URL_SAFE_NO_PAD.decode(s)).ok()
+    };
+    match bytes {
+        Some(b) if b.len() == 32 => Ok(STANDARD.encode(b)),
+        _ => Err(api_err(StatusCode::BAD_REQUEST, format!("{s:?} is not a 32-byte hex or base64 tx hash"))),
+    }
+}
+
+fn burn_json(b: &crate::repo::BurnDetail) -> serde_json::Value {
+    serde_json::json!({
+        "id": b.id,
+        "tx_hash_b64": b.tx_hash_b64,
+        "lt": b.lt,
+        "utime": b.utime,
+        "jetton_master_raw": b.jetton_master_raw,
+        "owner_raw": b.owner_raw,
+        "jetton_wallet_raw": b.jetton_wallet_raw,
+        "amount_raw": b.amount_raw,
+        "custom_payload_b64": b.custom_payload.as_ref().map(|p| base64::engine::general_purpose::STANDARD.encode(p)),
+        "created_at": b.created_at,
+    })
+}
+
+/// Full attestation: stored row, decoded payload, signatures and every submission attempt.
+async fn attestation_json(
+    db: &SqlitePool,
+    a: &crate::repo::AttDetail,
+    testnet: bool,
+) -> crate::errors::Result<serde_json::Value> {
+    Ok(serde_json::json!({
+        "id": a.id,
+        "kind": a.kind,
+        "hash_hex": a.payload_hash_hex,
+        "src_tx_hash_b64": a.src_tx_hash_b64,
+        "amount_dst": a.amount_dst,
+        "dust_raw": a.dust_raw,
+        "payload": crate::attestations::payload_json(&a.payload_borsh, testnet),
+        "signatures": crate::repo::list_signatures(db, a.id).await?,
+        "status": a.status,
+        "attempts": a.attempts,
+        "last_error": a.last_error,
+        "submissions": crate::repo::list_submissions(db, a.id).await?,
+        "sol_signature": a.sol_signature,
+        "confirmed_at": a.confirmed_at,
+        "confirmed_slot": a.confirmed_slot,
+        "created_at": a.created_at,
+        "updated_at": a.updated_at,
+    }))
+}
+
+/// One burn, with the hash and status of its attestation if there is one.
+#[utoipa::path(
+    get, path = "/burns/{tx_hash}", tag = "burns",
+    params(("tx_hash" = String, Path, description = "TON tx hash: hex, base64url or percent-encoded base64")),
+    responses(
+        (status = 200, description = "The burn and its attestation's hash and status", body = Object),
+        (status = 400, body = schema::Error),
+        (status = 404, body = schema::Error),
+    )
+)]
+async fn burn_by_hash(State(st): State<AppState>, Path(tx_hash): Path<String>) -> ApiResult {
+    let tx_hash = parse_tx_hash(&tx_hash)?;
+    let burn = crate::repo::get_burn(&st.db, &tx_hash)
+        .await?
+        .ok_or_else(|| api_err(StatusCode::NOT_FOUND, format!("burn {tx_hash} not found")))?;
+    let att = crate::repo::get_attestation_by_src_tx(&st.db, &tx_hash).await?;
+    let mut body = burn_json(&burn);
+    body["attestation"] = match att {
+        Some(a) => serde_json::json!({ "hash_hex": a.payload_hash_hex, "status": a.status }),
+        None => serde_json::Value::Null,
+    };
+    Ok(Json(body))
+}
+
+#[utoipa::path(
+    get, path = "/attestations/{hash}", tag = "attestations",
+    params(("hash" = String, Path, description = "Attestation hash, hex")),
+    responses(
+        (status = 200, description = "Row, payload, signatures and submission history", body = Object),
+        (status = 404, body = schema::Error),
+    )
+)]
+async fn attestation_by_hash(State(st): State<AppState>, Path(hash): Path<String>) -> ApiResult {
+    let hash = hash.trim_start_matches("0x").to_ascii_lowercase();
+    let att = crate::repo::get_attestation_by_hash(&st.db, &hash)
+        .await?
+        .ok_or_else(|| api_err(StatusCode::NOT_FOUND, format!("attestation {hash} not found")))?;
+    let body = attestation_json(&st.db, &att, st.cfg.ton_testnet()).await?;
+    Ok(Json(body))
+}
+
+/// "What happened to my transfer?": the burn, its quarantine entry or
+/// attestation, and the Solana transaction, with one summarising `stage`.
+#[utoipa::path(
+    get, path = "/transfers/{src_tx}", tag = "transfers",
+    params(("src_tx" = String, Path, description = "TON tx hash: hex, base64url or percent-encoded base64")),
+    responses(
+        (status = 200, description = "Burn, quarantine entry, attestation and destination with a summary `stage`", body = Object),
+        (status = 400, body = schema::Error),
+        (status = 404, body = schema::Error),
+    )
+)]
+async fn transfer(State(st): State<AppState>, Path(src_tx): Path<String>) -> ApiResult {
+    let src_tx = parse_tx_hash(&src_tx)?;
+    let db = &st.db;
+
+    let burn = crate::repo::get_burn(db, &src_tx).await?;
+    let att = crate::repo::get_attestation_by_src_tx(db, &src_tx).await?;
+    let quarantine = crate::repo::get_quarantine_by_tx(db, &src_tx).await?;
+    if burn.is_none() && att.is_none() && quarantine.is_none() {
+        return Err(api_err(StatusCode::NOT_FOUND, format!("no transfer for TON tx {src_tx}")));
+    }
+
+    let stage = match (&att, &quarantine) {
+        (Some(a), _) => match a.status.as_deref() {
+            Some("submitted") if a.confirmed_at.is_some() => "finalized",
+            Some("submitted") => "submitted",
+            Some("failed") => "failed",
+            Some("superseded") => "superseded",
+            // Rows from before the submission queue were signed but never tracked.
+            Some("pending") | None => "attested",
+            Some(_) => "unknown",
+        },
+        (None, Some(q)) if q.status == "held" || q.status == "released" => "quarantined",
+        (None, Some(_)) => "discarded",
+        (None, None) => "ingested",
+    };
+    let attestation = match &att {
+        Some(a) => attestation_json(db, a, st.cfg.ton_testnet()).await?,
+        None => serde_json::Value::Null,
+    };
+    let destination = att.as_ref().filter(|a| a.sol_signature.is_some()).map(|a| {
+        serde_json::json!({
+            "chain": "solana",
+            "cluster": st.cfg.sol_cluster,
+            "signature": a.sol_signature,
+            "status": a.status,
+            "finalized": a.confirmed_at.is_some(),
+            "confirmed_slot": a.confirmed_slot,
+            "confirmed_at": a.confirmed_at,
+        })
+    });
+
+    Ok(Json(serde_json::json!({
+        "src_tx_hash_b64": src_tx,
+        "stage": stage,
+        "burn": burn.as_ref().map(burn_json),
+        "quarantine": quarantine,
+        "attestation": attestation,
+        "destination": destination,
+    })))
+}
+
+#[derive(Deserialize, utoipa::IntoParams)]
+#[into_params(parameter_in = Query)]
+struct StatsQ {
+    /// Hourly buckets to return, 1 to 336, default 48.
+    hours: Option<i64>,
+    /// Daily buckets, and the window for outcomes, finalization and
+    /// destinations; 1 to 366, default 30.
+    days: Option<i64>,
+    /// Destinations per route, 1 to 100, default 10.
+    top: Option<i64>,
+}
+
+/// Bridge volume and health in aggregate, computed from the database on
+/// every call.
+#[utoipa::path(
+    get, path = "/stats", tag = "stats", params(StatsQ),
+    responses((status = 200, body = crate::stats::Stats))
+)]
+async fn stats(State(st): State<AppState>, Query(q): Query<StatsQ>) -> Result<Json<crate::stats::Stats>, ApiError> {
+    let window = crate::stats::Window::new(q.hours, q.days, q.top);
+    let now = crate::utils::now_ts() as i64;
+    Ok(Json(crate::stats::compute(&st.cfg, &st.db, window, now).await?))
+}
+
+#[derive(Deserialize, utoipa::IntoParams)]
+#[into_params(parameter_in = Query)]
+struct QuarantineQ { status: Option<String>, limit: Option<i64> }
+
+#[utoipa::path(
+    get, path = "/admin/quarantine", tag = "admin", params(QuarantineQ), security(("bearer" = [])),
+    responses((status = 200, description = "`items`, newest first", body = Object), schema::AdminErrors)
+)]
+async fn quarantine_list(State(st): State<AppState>, Query(q): Query<QuarantineQ>) -> ApiResult {
     let limit = q.limit.unwrap_or(50).clamp(1, 500);
-    let rows = crate::repo::list_burns(&st.db, limit).await.unwrap_or_default();
-    Json(serde_json::json!({ "items": rows }))
+    let rows = crate::repo::list_quarantine(&st.db, q.status.as_deref(), limit).await?;
+    Ok(Json(serde_json::json!({ "items": rows })))
+}
+
+type ApiResult = Result<Json<serde_json::Value>, ApiError>;
+
+fn api_err(code: StatusCode, msg: impl std::fmt::Display) -> ApiError {
+    ApiError::new(code, msg)
+}
+
+/// Mark a held burn for attestation; the ingest worker picks it up on its next tick.
+#[utoipa::path(
+    post, path = "/admin/quarantine/{id}/release", tag = "admin", params(("id" = i64, Path)), security(("bearer" = [])),
+    responses(
+        (status = 200, body = Object),
+        (status = 404, body = schema::Error),
+        (status = 409, description = "Not held", body = schema::Error),
+        (status = 422, description = "Malformed, cannot be released", body = schema::Error),
+        schema::AdminErrors,
+    )
+)]
+async fn quarantine_release(
+    State(st): State<AppState>,
+    Extension(who): Extension<Identity>,
+    Path(id): Path<i64>,
+) -> ApiResult {
+    let q = crate::repo::get_quarantine(&st.db, id)
+        .await?
+        .ok_or_else(|| api_err(StatusCode::NOT_FOUND, format!("quarantine #{id} not found")))?;
+    if q.status != "held" {
+        return Err(api_err(StatusCode::CONFLICT, format!("quarantine #{id} is {}", q.status)));
+    }
+    if !q.releasable {
+        return Err(api_err(
+            StatusCode::UNPROCESSABLE_ENTITY,
+            format!("quarantine #{id} ({}) is malformed and cannot be released", q.reason_code),
+        ));
+    }
+    crate::repo::set_quarantine_status(&st.db, id, "released", None).await?;
+    tracing::info!("quarantine #{id} released for attestation by {}", who.name);
+    Ok(Json(serde_json::json!({ "id": id, "status": "released" })))
 }
 
-async fn attestations(State(st): State<AppState>, Query(q): Query<ListQ>) -> Json<serde_json::Value> {
+#[utoipa::path(
+    post, path = "/admin/quarantine/{id}/discard", tag = "admin", params(("id" = i64, Path)), security(("bearer" = [])),
+    responses(
+        (status = 200, body = Object),
+        (status = 404, body = schema::Error),
+        (status = 409, description = "Not held", body = schema::Error),
+        schema::AdminErrors,
+    )
+)]
+async fn quarantine_discard(State(st): State<AppState>, Path(id): Path<i64>) -> ApiResult {
+    let q = crate::repo::get_quarantine(&st.db, id)
+        .await?
+        .ok_or_else(|| api_err(StatusCode::NOT_FOUND, format!("quarantine #{id} not found")))?;
+    if q.status != "held" {
+        return Err(api_err(StatusCode::CONFLICT, format!("quarantine #{id} is {}", q.status)));
+    }
+    crate::repo::set_quarantine_status(&st.db, id, "discarded", None).await?;
+    Ok(Json(serde_json::json!({ "id": id, "status": "discarded" })))
+}
+
+#[derive(Deserialize, utoipa::IntoParams)]
+#[into_params(parameter_in = Query)]
+struct AuditQ { before_id: Option<i64>, limit: Option<i64> }
+
+/// Admin calls newest first; page with `before_id`.
+#[utoipa::path(
+    get, path = "/admin/audit", tag = "admin", params(AuditQ), security(("bearer" = [])),
+    responses((status = 200, description = "`items` newest first and `next_before_id`", body = Object), schema::AdminErrors)
+)]
+async fn audit_list(State(st): State<AppState>, Query(q): Query<AuditQ>) -> ApiResult {
+    let limit = q.limit.unwrap_or(50).clamp(1, 500);
+    let rows = crate::repo::list_audit(&st.db, q.before_id, limit).await?;
+    let next_before_id = (rows.len() as i64 == limit).then(|| rows.last().map(|r| r.id)).flatten();
+    Ok(Json(serde_json::json!({ "items": rows, "next_before_id": next_before_id })))
+}
+
+#[utoipa::path(
+    get, path = "/admin/webhooks", tag = "admin", security(("bearer" = [])),
+    responses((status = 200, description = "`items`, without secrets", body = Object), schema::AdminErrors)
+)]
+async fn webhook_list(State(st): State<AppState>) -> ApiResult {
+    let rows = crate::repo::list_webhooks(&st.db).await?;
+    Ok(Json(serde_json::json!({ "items": rows })))
+}
+
+#[derive(Deserialize, utoipa::ToSchema)]
+struct WebhookReq {
+    url: String,
+    /// Comma-separated event types; all when omitted.
+    #[serde(default)]
+    types: String,
+    /// Generated when omitted.
+    secret: Option<String>,
+}
+
+/// Register a webhook. The signing secret is only ever returned here.
+#[utoipa::path(
+    post, path = "/admin/webhooks", tag = "admin", request_body = WebhookReq, security(("bearer" = [])),
+    responses(
+        (status = 200, description = "The webhook with its signing secret", body = Object),
+        (status = 400, body = schema::Error),
+        schema::AdminErrors,
+    )
+)]
+async fn webhook_create(
+    State(st): State<AppState>,
+    Extension(who): Extension<Identity>,
+    Json(req): Json<WebhookReq>,
+) -> ApiResult {
+    let url = reqwest::Url::parse(req.url.trim()).map_err(|e| api_err(StatusCode::BAD_REQUEST, format!("url: {e}")))?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(api_err(StatusCode::BAD_REQUEST, "url: scheme must be http or https"));
+    }
+    let types = crate::webhooks::parse_types(&req.types).map_err(|e| api_err(StatusCode::BAD_REQUEST, format!("types: {e}")))?;
+    let secret = match req.secret.filter(|s| !s.is_empty()) {
+        Some(s) if s.len() < 16 => return Err(api_err(StatusCode::BAD_REQUEST, "secret: at least 16 characters")),
+        Some(s) => s,
+        None => crate::webhooks::new_secret()?,
+    };
+    let id = crate::repo::insert_webhook(&st.db, url.as_str(), &secret, &types).await?;
+    tracing::info!(
+        "webhook #{id} registered for {url} by {} (types: {})",
+        who.name,
+        if types.is_empty() { "all" } else { &types }
+    );
+    Ok(Json(serde_json::json!({ "id": id, "url": url, "types": types, "secret": secret })))
+}
+
+#[utoipa::path(
+    delete, path = "/admin/webhooks/{id}", tag = "admin", params(("id" = i64, Path)), security(("bearer" = [])),
+    responses((status = 200, body = Object), (status = 404, body = schema::Error), schema::AdminErrors)
+)]
+async fn webhook_delete(State(st): State<AppState>, Path(id): Path<i64>) -> ApiResult {
+    let n = crate::repo::delete_webhook(&st.db, id).await?;
+    if n == 0 {
+        return Err(api_err(StatusCode::NOT_FOUND, format!("webhook #{id} not found")));
+    }
+    Ok(Json(serde_json::json!({ "id": id, "deleted": true })))
+}
+
+#[derive(Deserialize, utoipa::IntoParams)]
+#[into_params(parameter_in = Query)]
+struct DeadLetterQ { webhook_id: Option<i64>, limit: Option<i64> }
+
+#[utoipa::path(
+    get, path = "/admin/webhooks/dead_letters", tag = "admin", params(DeadLetterQ), security(("bearer" = [])),
+    responses((status = 200, description = "`items`, newest first", body = Object), schema::AdminErrors)
+)]
+async fn dead_letter_list(State(st): State<AppState>, Query(q): Query<DeadLetterQ>) -> ApiResult {
     let limit = q.limit.unwrap_or(50).clamp(1, 500);
-    let rows = crate::repo::list_attestations(&st.db, limit).await.unwrap_or_default();
-    Json(serde_json::json!({ "items": rows }))
+    let rows = crate::repo::list_dead_letters(&st.db, q.webhook_id, limit).await?;
+    Ok(Json(serde_json::json!({ "items": rows })))
+}
+
+/// Queue a dead letter for delivery again, with a fresh set of attempts.
+#[utoipa::path(
+    post, path = "/admin/webhooks/dead_letters/{id}/replay", tag = "admin", params(("id" = i64, Path)),
+    security(("bearer" = [])),
+    responses((status = 200, body = Object), (status = 404, body = schema::Error), schema::AdminErrors)
+)]
+async fn dead_letter_replay(State(st): State<AppState>, Path(id): Path<i64>) -> ApiResult {
+    let delivery = crate::repo::replay_dead_letter(&st.db, id)
+        .await?
+        .ok_or_else(|| api_err(StatusCode::NOT_FOUND, format!("dead letter #{id} not found or its webhook was deleted")))?;
+    Ok(Json(serde_json::json!({ "id": id, "delivery_id": delivery, "status": "queued" })))
+}
+
+#[derive(Deserialize, utoipa::IntoParams)]
+#[into_params(parameter_in = Query)]
+struct ReplayQ {
+    #[serde(default)]
+    dry_run: bool,
+    /// Resend an attestation that was already submitted.
+    #[serde(default)]
+    force: bool,
+}
+
+#[utoipa::path(
+    post, path = "/admin/attestations/{hash}/resubmit", tag = "admin",
+    params(("hash" = String, Path, description = "Attestation hash, hex"), ReplayQ), security(("bearer" = [])),
+    responses(
+        (status = 200, description = "What was (or, for a dry run, would be) done", body = Object),
+        (status = 404, body = schema::Error),
+        (status = 409, description = "Status does not allow it", body = schema::Error),
+        (status = 422, description = "Stored payload does not decode", body = schema::Error),
+        schema::AdminErrors,
+    )
+)]
+async fn attestation_resubmit(
+    State(st): State<AppState>,
+    Extension(who): Extension<Identity>,
+    Path(hash): Path<String>,
+    Query(q): Query<ReplayQ>,
+) -> ApiResult {
+    let hash = hash.trim_start_matches("0x").to_ascii_lowercase();
+    let plan = crate::replay::resubmit(&st.cfg, &st.db, &hash, q.force, q.dry_run, &who.name).await?;
+    Ok(Json(serde_json::json!(plan)))
 }
 
-async fn events(State(st): State<AppState>) -> Sse<impl futures::Stream<Item = Result<Event, Infallible>>> {
-    let mut rx = st.tx.subscribe();
+#[utoipa::path(
+    post, path = "/admin/burns/{tx_hash}/rebuild", tag = "admin",
+    params(("tx_hash" = String, Path, description = "TON tx hash: hex, base64url or percent-encoded base64"), ReplayQ),
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Current and rebuilt payloads with their diff", body = Object),
+        (status = 404, body = schema::Error),
+        (status = 409, description = "Current attestation cannot be replaced", body = schema::Error),
+        (status = 422, description = "Burn cannot be attested under the current config", body = schema::Error),
+        schema::AdminErrors,
+    )
+)]
+async fn burn_rebuild(
+    State(st): State<AppState>,
+    Extension(who): Extension<Identity>,
+    Path(tx_hash): Path<String>,
+    Query(q): Query<ReplayQ>,
+) -> ApiResult {
+    let tx_hash = parse_tx_hash(&tx_hash)?;
+    let plan = crate::replay::rebuild(&st.cfg, &st.db, &st.bus, &tx_hash, q.dry_run, &who.name).await?;
+    Ok(Json(serde_json::json!(plan)))
+}
+
+/// Current pauses plus every switch's last change.
+#[utoipa::path(
+    get, path = "/admin/pause", tag = "admin", security(("bearer" = [])),
+    responses((status = 200, description = "Pauses in effect and each switch's last change", body = Object), schema::AdminErrors)
+)]
+async fn pause_status(State(st): State<AppState>) -> ApiResult {
+    let rows = crate::repo::list_pauses(&st.db).await?;
+    let state = crate::pause::PauseState::load(&st.db).await?;
+    Ok(Json(serde_json::json!({ "paused": state, "switches": rows })))
+}
+
+/// Query rather than body, so `curl -X POST .../admin/pause` works as is.
+#[derive(Deserialize, utoipa::IntoParams)]
+#[into_params(parameter_in = Query)]
+struct PauseQ { route: Option<String>, reason: Option<String> }
+
+#[utoipa::path(
+    post, path = "/admin/pause", tag = "admin", params(PauseQ), security(("bearer" = [])),
+    responses((status = 200, description = "Pauses in effect", body = Object), (status = 404, body = schema::Error), schema::AdminErrors)
+)]
+async fn pause(State(st): State<AppState>, Extension(who): Extension<Identity>, Query(q): Query<PauseQ>) -> ApiResult {
+    set_pause(&st, &who, q, true).await
+}
+
+#[utoipa::path(
+    post, path = "/admin/resume", tag = "admin", params(PauseQ), security(("bearer" = [])),
+    responses((status = 200, description = "Pauses in effect", body = Object), (status = 404, body = schema::Error), schema::AdminErrors)
+)]
+async fn resume(State(st): State<AppState>, Extension(who): Extension<Identity>, Query(q): Query<PauseQ>) -> ApiResult {
+    set_pause(&st, &who, q, false).await
+}
+
+async fn set_pause(st: &AppState, who: &Identity, q: PauseQ, paused: bool) -> ApiResult {
+    let target = crate::pause::target(&st.cfg, q.route.as_deref()).map_err(|e| api_err(StatusCode::NOT_FOUND, e))?;
+    let reason = q.reason.as_deref().map(str::trim).filter(|r| !r.is_empty());
+    let state = crate::pause::set(&st.db, target, paused, reason, &who.name).await?;
+    Ok(Json(serde_json::json!({ "paused": state })))
+}
+
+/// Events replayed from the log per batch while catching a client up.
+const REPLAY_BATCH: i64 = 500;
+
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+#[into_params(parameter_in = Query)]
+struct EventsQ {
+    /// For clients that cannot set headers; `Last-Event-ID` wins.
+    last_event_id: Option<i64>,
+}
+
+fn sse_event(rec: &EventRecord) -> Event {
+    Event::default().id(rec.id.to_string()).event(rec.event.name()).data(rec.event.to_json())
+}
+
+/// Live event stream. Every event carries its log id; a client reconnecting
+/// with `Last-Event-ID` first gets what it missed from the log, and a client
+/// that falls behind the live channel gets a `lag` event and is caught up
+/// from the log the same way.
+#[utoipa::path(
+    get, path = "/events", tag = "events",
+    params(EventsQ, ("Last-Event-ID" = Option<i64>, Header, description = "Resume after this event id")),
+    responses(
+        (status = 200, description = "Server-sent events; `data` is one event", body = RelayerEvent, content_type = "text/event-stream"),
+        (status = 400, body = schema::Error),
+    )
+)]
+async fn events(
+    State(st): State<AppState>,
+    headers: HeaderMap,
+    Query(q): Query<EventsQ>,
+) -> Result<Sse<impl futures::Stream<Item = Result<Event, Infallible>>>, ApiError> {
+    let resume = match headers.get("last-event-id") {
+        Some(v) => Some(
+            v.to_str()
+                .ok()
+                .and_then(|s| s.trim().parse::<i64>().ok())
+                .ok_or_else(|| api_err(StatusCode::BAD_REQUEST, "Last-Event-ID must be an event id"))?,
+        ),
+        None => q.last_event_id,
+    };
+
+    // Subscribe before reading the log so nothing emitted in between is missed;
+    // the overlap is dropped by id below.
+    let mut rx = st.bus.subscribe();
+    let bus = st.bus.clone();
+    let shutdown = st.shutdown.clone();
     let stream = async_stream::stream! {
-        loop {
-            match rx.recv().await {
-                Ok(msg) => {
-                    yield Ok(Event::default().event("relayer").data(msg));
+        let _subscriber = crate::metrics::SubscriberGuard::acquire(&crate::metrics::METRICS.sse_subscribers);
+        let mut last = resume;
+
+        if let Some(after) = resume {
+            match bus.oldest_id().await {
+                Ok(Some(oldest)) if oldest > after + 1 => {
+                    let lag = serde_json::json!({ "reason": "expired", "requested_after": after, "oldest_id": oldest });
+                    yield Ok(Event::default().event("lag").data(lag.to_string()));
+                }
+                Ok(_) => {}
+                Err(e) => tracing::warn!("events: reading log failed: {e}"),
+            }
+        }
+
+        'stream: loop {
+            // Catch up from the log until a short batch shows we are at the head.
+            if let Some(mut after) = last {
+                loop {
+                    let batch = match bus.replay(after, REPLAY_BATCH).await {
+                        Ok(b) => b,
+                        Err(e) => {
+                            // Ending the stream makes the client reconnect with its Last-Event-ID.
+                            tracing::warn!("events: replay after {after} failed: {e}");
+                            break 'stream;
+                        }
+                    };
+                    for rec in &batch {
+                        yield Ok(sse_event(rec));
+                        after = rec.id;
+                    }
+                    if (batch.len() as i64) < REPLAY_BATCH {
+                        break;
+                    }
                 }
-                Err(_) => {
-                    tokio::time::sleep(Duration::from_millis(200)).await;
+                last = Some(after);
+            }
+
+            loop {
+                tokio::select! {
+                    msg = rx.recv() => match msg {
+                        Ok(rec) => {
+                            if last.is_some_and(|l| rec.id <= l) {
+                                continue;
+                            }
+                            last = Some(rec.id);
+                            yield Ok(sse_event(&rec));
+                        }
+                        Err(RecvError::Closed) => break 'stream,
+                        Err(RecvError::Lagged(missed)) => {
+                            // Without a position there is nothing to replay from.
+                            let lag = serde_json::json!({
+                                "reason": "lagged",
+                                "missed": missed,
+                                "replayed": last.is_some(),
+                            });
+                            yield Ok(Event::default().event("lag").data(lag.to_string()));
+                            continue 'stream;
+                        }
+                    },
+                    _ = shutdown.cancelled() => {
+                        // Flush what the workers emitted while stopping, then tell the client.
+                        while let Ok(rec) = rx.try_recv() {
+                            if last.is_none_or(|l| rec.id > l) {
+                                yield Ok(sse_event(&rec));
+                            }
+                        }
+                        yield Ok(Event::default().event("shutdown").data("{}"));
+                        break 'stream;
+                    }
                 }
             }
         }
     };
-    Sse::new(stream)
+    Ok(Sse::new(stream))
+}
+
+/// WebSocket variant of `/events` with server-side filters; see [`crate::ws::session`].
+#[utoipa::path(
+    get, path = "/ws", tag = "events", params(crate::ws::WsQ),
+    responses(
+        (status = 101, description = "WebSocket; text frames carry events or control messages"),
+        (status = 400, body = schema::Error),
+    )
+)]
+async fn events_ws(
+    State(st): State<AppState>,
+    Query(q): Query<crate::ws::WsQ>,
+    ws: WebSocketUpgrade,
+) -> Result<Response, ApiError> {
+    let filter = q.filter().normalize(&st.cfg).map_err(|e| api_err(StatusCode::BAD_REQUEST, e))?;
+    let resume = q.last_event_id;
+    Ok(ws.max_message_size(64 * 1024).on_upgrade(move |socket| crate::ws::session(socket, st, filter, resume)))
+}
+
+#[derive(utoipa::OpenApi)]
+#[openapi(
+    info(title = "bridge-relayer", description = "TON to Solana bridge relayer: read API, event streams and admin API."),
+    paths(
+        health, live, ready, metrics, config_hash, routes, openapi, docs,
+        burns, burn_by_hash, attestations, attestation_by_hash, transfer, stats, events, events_ws,
+        quarantine_list, quarantine_release, quarantine_discard, audit_list,
+        webhook_list, webhook_create, webhook_delete, dead_letter_list, dead_letter_replay,
+        pause_status, pause, resume, attestation_resubmit, burn_rebuild,
+    ),
+    components(schemas(
+        crate::repo::BurnRow, crate::repo::AttRow, schema::Payload,
+        crate::events::BurnEvent, crate::events::AttestationEvent, crate::events::QuarantineEvent,
+        crate::events::SubmissionEvent, crate::events::SubmissionStatus, crate::events::PauseEvent,
+        crate::events::ConfirmationEvent,
+    )),
+    modifiers(&BearerAuth),
+)]
+struct ApiDoc;
+
+/// Types that only describe response bodies in the OpenAPI document; the
+/// handlers build those bodies with `json!`.
+#[allow(dead_code)]
+mod schema {
+    use crate::repo::{AttRow, BurnRow};
+    use utoipa::openapi::security::{Http, HttpAuthScheme, SecurityScheme};
+
+    /// Body of every 4xx/5xx response.
+    #[derive(utoipa::ToSchema)]
+    pub struct Error {
+        /// For people; 5xx errors only say `internal error`.
+        pub error: String,
+        /// Stable, for programs: `bad_request`, `not_found`, `conflict`,
+        /// `storage`, `network`, ...
+        pub code: String,
+        /// Also in the `X-Request-Id` header and in the server's logs.
+        pub request_id: Option<String>,
+    }
+
+    #[derive(utoipa::ToSchema)]
+    pub struct BurnPage {
+        pub items: Vec<BurnRow>,
+        /// Pass as `before_id` for the next (older) page; null on the last.
+        pub next_before_id: Option<i64>,
+        /// Pass as `after_id` for the previous (newer) page.
+        pub prev_after_id: Option<i64>,
+    }
+
+    #[derive(utoipa::ToSchema)]
+    pub struct Attestation {
+        #[schema(inline)]
+        #[serde(flatten)]
+        pub row: AttRow,
+        pub payload: Payload,
+    }
+
+    #[derive(utoipa::ToSchema)]
+    pub struct AttestationPage {
+        pub items: Vec<Attestation>,
+        pub next_before_id: Option<i64>,
+        pub prev_after_id: Option<i64>,
+    }
+
+    /// An attestation payload as signed and submitted.
+    #[derive(utoipa::ToSchema)]
+    pub struct Payload {
+        /// The exact signed bytes.
+        pub borsh_b64: String,
+        pub domain: String,
+        /// `sha256(domain || borsh)`, what relayer signatures cover.
+        pub hash_hex: String,
+        #[schema(value_type = Object)]
+        pub decoded: serde_json::Value,
+    }
+
+    #[derive(utoipa::IntoResponses)]
+    pub enum AdminErrors {
+        /// Missing or unknown bearer token.
+        #[response(status = 401)]
+        Unauthorized(Error),
+        /// The token's scope does not cover this call.
+        #[response(status = 403)]
+        Forbidden(Error),
+    }
+
+    /// Adds the `bearer` scheme the admin paths refer to.
+    pub struct BearerAuth;
+
+    impl utoipa::Modify for BearerAuth {
+        fn modify(&self, api: &mut utoipa::openapi::OpenApi) {
+            let components = api.components.get_or_insert_with(Default::default);
+            components.add_security_scheme("bearer", SecurityScheme::Http(Http::new(HttpAuthScheme::Bearer)));
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use std::collections::BTreeSet;
+    use std::net::SocketAddr;
+
+    /// App state over `db` with no dependencies wired up.
+    pub(crate) fn state(cfg: Cfg, db: SqlitePool) -> AppState {
+        AppState {
+            cfg: Arc::new(cfg),
+            db: db.clone(),
+            bus: EventBus::new(db, 16),
+            sup: Supervisor::default(),
+            deps: Default::default(),
+            shutdown: CancellationToken::new(),
+        }
+    }
+
+    /// Serves `app` on an ephemeral local port, with peer addresses.
+    pub(crate) async fn listen(app: Router) -> SocketAddr {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let app = app.into_make_service_with_connect_info::<SocketAddr>();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        addr
+    }
+
+    /// The full API over `state`, as the relayer serves it.
+    pub(crate) async fn serve(state: AppState) -> SocketAddr {
+        listen(router(state)).await
+    }
+
+    /// (method, path) of every route in [`endpoints`], which [`router`] is
+    /// built from, so that a new route cannot be added without the test noticing.
+    fn routed() -> BTreeSet<(String, String)> {
+        endpoints()
+            .into_iter()
+            .map(|e| {
+                let path: Vec<String> = e
+                    .path
+                    .split('/')
+                    .map(|seg| seg.strip_prefix(':').map(|p| format!("{{{p}}}")).unwrap_or(seg.into()))
+                    .collect();
+                (e.method.as_str().to_lowercase(), path.join("/"))
+            })
+            .collect()
+    }
+
+    fn documented() -> BTreeSet<(String, String)> {
+        let mut out = BTreeSet::new();
+        for (path, item) in ApiDoc::openapi().paths.paths {
+            for (method, op) in [("get", &item.get), ("post", &item.post), ("delete", &item.delete)] {
+                if op.is_some() {
+                    out.insert((method.to_string(), path.clone()));
+                }
+            }
+        }
+        out
+    }
+
+    #[tokio::test]
+    async fn docs_page_loads_nothing_from_other_hosts() {
+        let db = crate::repo::tests::temp_db().await;
+        let base = format!("http://{}", serve(state(crate::config::tests::cfg_with(""), db)).await);
+        let res = reqwest::get(format!("{base}/docs")).await.unwrap();
+        assert!(res.headers()["content-security-policy"].to_str().unwrap().starts_with("default-src 'none'"));
+        assert!(!res.text().await.unwrap().contains("://"));
+    }
+
+    #[tokio::test]
+    async fn transfer_is_finalized_once_its_submission_is_confirmed() {
+        use crate::repo::tests::{attestation, burn};
+        let db = crate::repo::tests::temp_db().await;
+        let tx = base64::engine::general_purpose::STANDARD.encode([7u8; 32]);
+        let b = burn(&tx, 42, 1_700_000_000, "0:AA", "0:BB", "5");
+        let id = crate::repo::insert_attested_burn(&db, &b, &attestation("ab", &tx, &[2u8; 8])).await.unwrap().unwrap();
+        crate::repo::record_submission(&db, id, "submitted", None, Some("sig")).await.unwrap();
+        let base = format!("http://{}", serve(state(crate::config::tests::cfg_with(""), db.clone())).await);
+        let get = || async {
+            let url = format!("{base}/transfers/{}", hex::encode([7u8; 32]));
+            reqwest::get(url).await.unwrap().json::<serde_json::Value>().await.unwrap()
+        };
+
+        let body = get().await;
+        assert_eq!(body["stage"], "submitted");
+        assert_eq!(body["destination"]["signature"], "sig");
+        assert_eq!(body["destination"]["finalized"], false);
+
+        assert!(crate::repo::confirm_attestation(&db, id, 1234).await.unwrap());
+        let body = get().await;
+        assert_eq!(body["stage"], "finalized");
+        assert_eq!((body["destination"]["finalized"].as_bool(), body["destination"]["confirmed_slot"].as_i64()), (Some(true), Some(1234)));
+        assert_eq!(body["attestation"]["confirmed_slot"], 1234);
+    }
+
+    #[tokio::test]
+    async fn errors_are_json_with_a_code_and_request_id() {
+        let db = crate::repo::tests::temp_db().await;
+        let base = format!("http://{}", serve(state(crate::config::tests::cfg_with(""), db.clone())).await);
+        let c = reqwest::Client::new();
+        let get = |path: &str| c.get(format!("{base}{path}")).header(REQUEST_ID_HEADER, "req-1").send();
+
+        let res = get("/burns?limit=ten").await.unwrap();
+        assert_eq!(res.status(), 400);
+        assert_eq!(res.headers()[REQUEST_ID_HEADER], "req-1");
+        let body: serde_json::Value = res.json().await.unwrap();
+        assert_eq!((body["code"].as_str(), body["request_id"].as_str()), (Some("bad_request"), Some("req-1")));
+        assert!(body["error"].as_str().unwrap().contains("query string"), "{body}");
+
+        let body: serde_json::Value = get("/nope").await.unwrap().json().await.unwrap();
+        assert_eq!(body["code"], "not_found");
+
+        // A database failure is a 500, not an empty page.
+        db.close().await;
+        let res = get("/burns").await.unwrap();
+        assert_eq!(res.status(), 500);
+        let body: serde_json::Value = res.json().await.unwrap();
+        assert_eq!((body["code"].as_str(), body["error"].as_str()), (Some("storage"), Some("internal error")));
+
+        let res = c.get(format!("{base}/attestations")).send().await.unwrap();
+        let id = res.headers()[REQUEST_ID_HEADER].to_str().unwrap().to_string();
+        assert_eq!(id.len(), 16);
+        let body: serde_json::Value = res.json().await.unwrap();
+        assert_eq!(body["request_id"], id.as_str());
+    }
+
+    #[tokio::test]
+    async fn public_routes_get_cors_compression_and_a_rate_limit() {
+        let cfg = crate::config::tests::cfg_with(
+            r#"
+            [api]
+            cors_origins = ["https://dash.example.com"]
+            rate_limit = 60
+            rate_limit_burst = 2
+            "#,
+        );
+        let addr = serve(state(cfg, crate::repo::tests::temp_db().await)).await;
+        let base = format!("http://{addr}");
+        let c = reqwest::Client::new();
+
+        // Raw request: reqwest may decompress and drop Content-Encoding.
+        let mut conn = tokio::net::TcpStream::connect(addr).await.unwrap();
+        let req = "GET /openapi.json HTTP/1.1\r\nhost: x\r\norigin: https://dash.example.com\r\n\
+                   accept-encoding: gzip\r\nconnection: close\r\n\r\n";
+        tokio::io::AsyncWriteExt::write_all(&mut conn, req.as_bytes()).await.unwrap();
+        let mut raw = Vec::new();
+        tokio::io::AsyncReadExt::read_to_end(&mut conn, &mut raw).await.unwrap();
+        let head = String::from_utf8_lossy(&raw[..raw.windows(4).position(|w| w == b"\r\n\r\n").unwrap()]).to_lowercase();
+        assert!(head.starts_with("http/1.1 200"), "{head}");
+        assert!(head.contains("access-control-allow-origin: https://dash.example.com"), "{head}");
+        assert!(head.contains("content-encoding: gzip"), "{head}");
+        let res = c.get(format!("{base}/routes")).header("origin", "https://evil.example.com").send().await.unwrap();
+        assert!(res.headers().get("access-control-allow-origin").is_none());
+
+        // Burst of 2 spent; readiness and metrics share it, liveness does not.
+        let res = c.get(format!("{base}/routes")).send().await.unwrap();
+        assert_eq!(res.status(), 429);
+        assert_eq!(c.get(format!("{base}/health/ready")).send().await.unwrap().status(), 429);
+        assert_eq!(c.get(format!("{base}/metrics")).send().await.unwrap().status(), 429);
+        assert!(res.headers().contains_key("retry-after"));
+        let body: serde_json::Value = res.json().await.unwrap();
+        assert_eq!(body["code"], "rate_limited");
+        assert_eq!(c.get(format!("{base}/health/live")).send().await.unwrap().status(), 200);
+    }
+
+    #[test]
+    fn openapi_covers_exactly_the_routes() {
+        let routed = routed();
+        assert!(routed.contains(&("delete".into(), "/admin/webhooks/{id}".into())));
+        let documented = documented();
+        assert_eq!(
+            routed.difference(&documented).collect::<Vec<_>>(),
+            Vec::<&(String, String)>::new(),
+            "routes without an OpenAPI entry; add #[utoipa::path] and list them in ApiDoc"
+        );
+        assert_eq!(
+            documented.difference(&routed).collect::<Vec<_>>(),
+            Vec::<&(String, String)>::new(),
+            "OpenAPI entries for routes that do not exist"
+        );
+
+        let doc = serde_json::to_value(ApiDoc::openapi()).unwrap();
+        assert!(doc["openapi"].as_str().unwrap().starts_with("3."));
+        assert!(doc["components"]["schemas"]["BurnRow"].is_object());
+        assert!(doc["components"]["schemas"]["AttRow"].is_object());
+        assert!(doc["components"]["securitySchemes"]["bearer"].is_object());
+    }
 }
@@ -1,5 +1,9 @@
 use borsh::{BorshSerialize, BorshDeserialize};
 
+use crate::ton::address::TonAddress;
+
+/// Original layout, without the SPL amount. No longer signed; kept so
+/// payloads stored by earlier relayers still decode.
 #[derive(Debug, Clone, BorshSerialize, BorshDeserialize)]
 pub struct TonToSolAttestationV1 {
     pub ver: u8,
@@ -18,6 +22,201 @@ pub struct TonToSolAttestationV1 {
     pub timestamp_ton: u64,
 }
 
+/// V1 plus the destination amount: `amount_sol` and `decimals_sol` follow
+/// `decimals_ton`. Signed under [`TON_TO_SOL_DOMAIN_V2`] with `ver = 2`.
+#[derive(Debug, Clone, BorshSerialize, BorshDeserialize)]
+pub struct TonToSolAttestationV2 {
+    pub ver: u8,
+    pub src_chain: u8,
+    pub kind: u8,
+    pub cfg_hash: [u8; 32],
+    pub nonce: u64,
+    pub jetton_minter_ton: [u8; 36],
+    pub amount_raw: u128,
+    pub decimals_ton: u8,
+    /// `amount_raw` converted to SPL base units of the route's mint.
+    pub amount_sol: u64,
+    pub decimals_sol: u8,
+    pub dst_solana_pubkey: [u8; 32],
+    pub min_sol_out: Option<u64>,
+    pub deadline_ts: Option<u64>,
+    pub tx_hash_ton: [u8; 32],
+    pub lt_ton: u64,
+    pub timestamp_ton: u64,
+}
+
+/// Byte offset of `dst_solana_pubkey` in the Borsh encoding; every field
+/// before it is fixed-size. The layouts share their first 96 bytes:
+///
+/// | offset | V1 | V2 |
+/// |---|---|---|
+/// | 0 | `ver` = 1 | `ver` = 2 |
+/// | 1, 2 | `src_chain`, `kind` | same |
+/// | 3 | `cfg_hash` (32) | same |
+/// | 35 | `nonce` (u64) | same |
+/// | 43 | `jetton_minter_ton` (36) | same |
+/// | 79 | `amount_raw` (u128) | same |
+/// | 95 | `decimals_ton` | same |
+/// | 96 | `dst_solana_pubkey` (32) | `amount_sol` (u64) |
+/// | 104 | | `decimals_sol` |
+/// | 105 | | `dst_solana_pubkey` (32) |
+///
+/// followed in both by the optional and trailing fields. `repo::top_destinations`
+/// reads the account in SQL, picking the offset by the `ver` byte.
+pub const DST_OFFSET_V1: usize = 96;
+pub const DST_OFFSET: usize = 105;
+
+/// A stored TON→SOL payload of either layout, told apart by its `ver` byte.
+#[derive(Debug, Clone)]
+pub enum TonToSolAttestation {
+    V1(TonToSolAttestationV1),
+    V2(TonToSolAttestationV2),
+}
+
+impl TonToSolAttestation {
+    pub fn decode(bytes: &[u8]) -> std::io::Result<Self> {
+        match bytes.first() {
+            Some(1) => Ok(Self::V1(TonToSolAttestationV1::try_from_slice(bytes)?)),
+            Some(2) => Ok(Self::V2(TonToSolAttestationV2::try_from_slice(bytes)?)),
+            Some(v) => Err(std::io::Error::new(std::io::ErrorKind::InvalidData, format!("unknown version {v}"))),
+            None => Err(std::io::Error::new(std::io::ErrorKind::UnexpectedEof, "empty payload")),
+        }
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        match self {
+            Self::V1(a) => borsh::to_vec(a),
+            Self::V2(a) => borsh::to_vec(a),
+        }
+        .expect("borsh to Vec cannot fail")
+    }
+
+    /// Domain the signature over this payload is taken in.
+    pub fn domain(&self) -> &'static str {
+        match self {
+            Self::V1(_) => TON_TO_SOL_DOMAIN,
+            Self::V2(_) => TON_TO_SOL_DOMAIN_V2,
+        }
+    }
+
+    pub fn jetton_minter_ton(&self) -> [u8; 36] {
+        match self {
+            Self::V1(a) => a.jetton_minter_ton,
+            Self::V2(a) => a.jetton_minter_ton,
+        }
+    }
+
+    pub fn dst_solana_pubkey(&self) -> [u8; 32] {
+        match self {
+            Self::V1(a) => a.dst_solana_pubkey,
+            Self::V2(a) => a.dst_solana_pubkey,
+        }
+    }
+
+    /// Field-by-field view for API consumers. Addresses are shown both raw and
+    /// in their usual human form (TON user-friendly, Solana base58), hashes in
+    /// hex, and integers that can exceed 2^53 as strings. `testnet` selects
+    /// the TON friendly-address flag. V1 payloads have no `amount_sol` or
+    /// `decimals_sol`.
+    pub fn to_json(&self, testnet: bool) -> serde_json::Value {
+        use base64::Engine;
+        // The fields both layouts share.
+        macro_rules! common {
+            ($a:expr) => {{
+                let minter = TonAddress::from_wc_hash36(&$a.jetton_minter_ton);
+                serde_json::json!({
+                    "ver": $a.ver,
+                    "src_chain": $a.src_chain,
+                    "kind": $a.kind,
+                    "cfg_hash": hex::encode($a.cfg_hash),
+                    "nonce": $a.nonce.to_string(),
+                    "jetton_minter_ton": minter.to_friendly(true, testnet),
+                    "jetton_minter_ton_raw": minter.to_raw(),
+                    "amount_raw": $a.amount_raw.to_string(),
+                    "decimals_ton": $a.decimals_ton,
+                    "dst_solana_pubkey": bs58::encode($a.dst_solana_pubkey).into_string(),
+                    "min_sol_out": $a.min_sol_out.map(|v| v.to_string()),
+                    "deadline_ts": $a.deadline_ts,
+                    "tx_hash_ton": base64::engine::general_purpose::STANDARD.encode($a.tx_hash_ton),
+                    "tx_hash_ton_hex": hex::encode($a.tx_hash_ton),
+                    "lt_ton": $a.lt_ton.to_string(),
+                    "timestamp_ton": $a.timestamp_ton,
+                })
+            }};
+        }
+        match self {
+            Self::V1(a) => common!(a),
+            Self::V2(a) => {
+                let mut v = common!(a);
+                v["amount_sol"] = a.amount_sol.to_string().into();
+                v["decimals_sol"] = a.decimals_sol.into();
+                v
+            }
+        }
+    }
+}
+
+/// Raw Borsh bytes together with their decoding and the signed hash, so a
+/// client can re-encode the fields and check them against the signature.
+pub fn payload_json(borsh_bytes: &[u8], testnet: bool) -> serde_json::Value {
+    use base64::Engine;
+    // Undecodable bytes are hashed as what this relayer signs now.
+    let (decoded, domain) = match TonToSolAttestation::decode(borsh_bytes) {
+        Ok(att) => (att.to_json(testnet), att.domain()),
+        Err(e) => (serde_json::json!({ "error": format!("payload does not decode: {e}") }), TON_TO_SOL_DOMAIN_V2),
+    };
+    serde_json::json!({
+        "borsh_b64": base64::engine::general_purpose::STANDARD.encode(borsh_bytes),
+        "domain": domain,
+        "hash_hex": hex::encode(domain_hash(domain, borsh_bytes)),
+        "decoded": decoded,
+    })
+}
+
+pub const CHAIN_TON: u8 = 0;
+pub const CHAIN_SOLANA: u8 = 1;
+
+pub const CFG_DOMAIN: &str = "BRIDGE_CFG_V1";
+/// Domain of the hash a V1 attestation signature covers.
+pub const TON_TO_SOL_DOMAIN: &str = "TON→SOL_BRIDGE_V1";
+/// Domain of the hash a V2 attestation signature covers; a new domain, so a
+/// V2 signature can never verify as a V1 message.
+pub const TON_TO_SOL_DOMAIN_V2: &str = "TON→SOL_BRIDGE_V2";
+
+/// Canonical description of the bridge parameters. `cfg_hash` in every
+/// attestation is `domain_hash(CFG_DOMAIN, borsh(CfgPreimageV1))`, so the
+/// field order and encoding here are part of the on-chain contract.
+#[derive(Debug, Clone, BorshSerialize, BorshDeserialize)]
+pub struct CfgPreimageV1 {
+    pub ver: u8,
+    pub src_chain: u8,
+    pub dst_chain: u8,
+    pub ton_global_id: i32,
+    pub sol_cluster: String,
+    pub ton_bridge: [u8; 36],
+    pub sol_bridge_program: [u8; 32],
+    /// Sorted by `jetton_minter_ton`.
+    pub routes: Vec<RoutePreimageV1>,
+    /// Sorted, deduplicated ed25519 pubkeys.
+    pub signers: Vec<[u8; 32]>,
+    pub threshold: u8,
+}
+
+#[derive(Debug, Clone, BorshSerialize, BorshDeserialize)]
+pub struct RoutePreimageV1 {
+    pub jetton_minter_ton: [u8; 36],
+    pub decimals_ton: u8,
+    pub sol_mint: [u8; 32],
+    pub decimals_sol: u8,
+}
+
+impl CfgPreimageV1 {
+    pub fn hash(&self) -> [u8; 32] {
+        let bytes = borsh::to_vec(self).expect("borsh to Vec cannot fail");
+        domain_hash(CFG_DOMAIN, &bytes)
+    }
+}
+
 pub fn domain_hash(domain: &str, msg: &[u8]) -> [u8; 32] {
     use sha2::{Digest, Sha256};
     let mut h = Sha256::new();
@@ -27,3 +226,68 @@ pub fn domain_hash(domain: &str, msg: &[u8]) -> [u8; 32] {
     out.copy_from_slice(&h.finalize());
     out
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn payload_json_renders_human_addresses() {
+        let master = TonAddress { workchain: 0, hash: [0xAB; 32] };
+        let dst = solana_sdk::pubkey::Pubkey::new_from_array([7; 32]);
+        let att = TonToSolAttestationV2 {
+            ver: 2,
+            src_chain: CHAIN_TON,
+            kind: 1,
+            cfg_hash: [1; 32],
+            nonce: 0,
+            jetton_minter_ton: master.wc_hash36(),
+            amount_raw: u128::MAX,
+            decimals_ton: 9,
+            amount_sol: 5,
+            decimals_sol: 6,
+            dst_solana_pubkey: dst.to_bytes(),
+            min_sol_out: None,
+            deadline_ts: None,
+            tx_hash_ton: [2; 32],
+            lt_ton: 42,
+            timestamp_ton: 1_700_000_000,
+        };
+        let bytes = borsh::to_vec(&att).unwrap();
+        let v = payload_json(&bytes, true);
+
+        let d = &v["decoded"];
+        let friendly: TonAddress = d["jetton_minter_ton"].as_str().unwrap().parse().unwrap();
+        assert_eq!(friendly, master);
+        assert_eq!(d["dst_solana_pubkey"], dst.to_string());
+        assert_eq!(d["amount_raw"], u128::MAX.to_string());
+        assert_eq!(d["amount_sol"], "5");
+        assert_eq!(v["hash_hex"], hex::encode(domain_hash(TON_TO_SOL_DOMAIN_V2, &bytes)));
+        assert!(payload_json(&bytes[1..], true)["decoded"]["error"].is_string());
+        assert_eq!(bytes[DST_OFFSET..DST_OFFSET + 32], dst.to_bytes());
+
+        // Payloads signed before V2 still decode, under their own domain.
+        let v1 = TonToSolAttestationV1 {
+            ver: 1,
+            src_chain: att.src_chain,
+            kind: att.kind,
+            cfg_hash: att.cfg_hash,
+            nonce: att.nonce,
+            jetton_minter_ton: att.jetton_minter_ton,
+            amount_raw: att.amount_raw,
+            decimals_ton: att.decimals_ton,
+            dst_solana_pubkey: att.dst_solana_pubkey,
+            min_sol_out: None,
+            deadline_ts: None,
+            tx_hash_ton: att.tx_hash_ton,
+            lt_ton: att.lt_ton,
+            timestamp_ton: att.timestamp_ton,
+        };
+        let old = borsh::to_vec(&v1).unwrap();
+        let v = payload_json(&old, true);
+        assert_eq!(v["domain"], TON_TO_SOL_DOMAIN);
+        assert_eq!(v["decoded"]["dst_solana_pubkey"], dst.to_string());
+        assert!(v["decoded"].get("amount_sol").is_none());
+        assert_eq!(old[DST_OFFSET_V1..DST_OFFSET_V1 + 32], dst.to_bytes());
+    }
+}
@@ -0,0 +1,184 @@
+use axum::{
+    extract::{ConnectInfo, Request, State},
+    http::{header, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use sha2::{Digest, Sha256};
+use std::{net::SocketAddr, str::FromStr};
+
+use crate::api::AppState;
+use crate::errors::ApiError;
+
+/// Admin permission levels; each includes the ones below it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Scope {
+    /// Inspect quarantine, webhooks and the audit log.
+    Read,
+    /// Act on individual items: release, discard, replay.
+    Operator,
+    /// Change configuration held in the database, such as webhooks.
+    Admin,
+}
+
+impl Scope {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Scope::Read => "read",
+            Scope::Operator => "operator",
+            Scope::Admin => "admin",
+        }
+    }
+}
+
+impl FromStr for Scope {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        match s.trim() {
+            "read" | "read_only" | "read-only" => Ok(Scope::Read),
+            "operator" => Ok(Scope::Operator),
+            "admin" => Ok(Scope::Admin),
+            _ => Err(format!("unknown scope {s:?}, expected read, operator or admin")),
+        }
+    }
+}
+
+/// A configured bearer token. Only its SHA-256 is kept, so the config does
+/// not hold usable credentials.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AdminToken {
+    pub name: String,
+    pub scope: Scope,
+    pub sha256: [u8; 32],
+}
+
+/// Who made an admin call; added to the request extensions for handlers.
+#[derive(Debug, Clone)]
+pub struct Identity {
+    /// The token's configured name.
+    pub name: String,
+}
+
+fn find<'a>(tokens: &'a [AdminToken], bearer: &str) -> Option<&'a AdminToken> {
+    let digest: [u8; 32] = Sha256::digest(bearer.as_bytes()).into();
+    // Compare every byte of every entry so timing says nothing about which matched.
+    let mut found = None;
+    for t in tokens {
+        let diff = t.sha256.iter().zip(digest.iter()).fold(0u8, |acc, (a, b)| acc | (a ^ b));
+        if diff == 0 {
+            found = Some(t);
+        }
+    }
+    found
+}
+
+/// Middleware for admin routes: checks the bearer token against `required`
+/// and writes one `admin_audit` row per call, allowed or not.
+pub async fn require(State(st): State<AppState>, required: Scope, mut req: Request, next: Next) -> Response {
+    let method = req.method().to_string();
+    let path = req.uri().path_and_query().map(|p| p.to_string()).unwrap_or_default();
+    let remote = req.extensions().get::<ConnectInfo<SocketAddr>>().map(|c| c.0.to_string());
+
+    let bearer = req
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .map(str::trim);
+    let token = bearer.and_then(|b| find(&st.cfg.admin_tokens, b));
+
+    let res = match token {
+        None => {
+            let msg = if st.cfg.admin_tokens.is_empty() {
+                "admin API disabled: no admin tokens configured"
+            } else {
+                "missing or unknown bearer token"
+            };
+            let mut res = ApiError::new(StatusCode::UNAUTHORIZED, msg).into_response();
+            res.headers_mut().insert(header::WWW_AUTHENTICATE, header::HeaderValue::from_static("Bearer"));
+            res
+        }
+        Some(t) if t.scope < required => ApiError::new(
+            StatusCode::FORBIDDEN,
+            format!("token {:?} has scope {}, {} required", t.name, t.scope.as_str(), required.as_str()),
+        )
+        .into_response(),
+        Some(t) => {
+            req.extensions_mut().insert(Identity { name: t.name.clone() });
+            next.run(req).await
+        }
+    };
+
+    let status = res.status().as_u16() as i64;
+    let entry = crate::repo::AuditEntry {
+        token_name: token.map(|t| t.name.clone()),
+        scope: token.map(|t| t.scope.as_str()),
+        method,
+        path,
+        status,
+        remote_addr: remote,
+    };
+    if let Err(e) = crate::repo::insert_audit(&st.db, &entry).await {
+        tracing::error!("admin audit write failed for {} {}: {e}", entry.method, entry.path);
+    }
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn token(name: &str, scope: Scope, secret: &str) -> AdminToken {
+        AdminToken { name: name.into(), scope, sha256: Sha256::digest(secret.as_bytes()).into() }
+    }
+
+    #[test]
+    fn scopes_are_ordered_and_tokens_match_by_hash() {
+        assert!(Scope::Read < Scope::Operator && Scope::Operator < Scope::Admin);
+        assert_eq!("read-only".parse::<Scope>(), Ok(Scope::Read));
+        assert!("root".parse::<Scope>().is_err());
+
+        let tokens = [token("ops", Scope::Operator, "aaa"), token("ci", Scope::Read, "bbb")];
+        assert_eq!(find(&tokens, "bbb").map(|t| t.name.as_str()), Some("ci"));
+        assert!(find(&tokens, "ccc").is_none());
+        assert!(find(&[], "aaa").is_none());
+    }
+
+    #[tokio::test]
+    async fn admin_calls_need_a_scoped_token_and_are_audited() {
+        let hash = |s: &str| hex::encode(Sha256::digest(s.as_bytes()));
+        let cfg = crate::config::tests::cfg_with(&format!(
+            r#"
+            [[admin.tokens]]
+            name = "viewer"
+            scope = "read"
+            sha256 = "{}"
+            [[admin.tokens]]
+            name = "ops"
+            scope = "operator"
+            sha256 = "{}"
+            "#,
+            hash("view-token"),
+            hash("ops-token")
+        ));
+        let db = crate::repo::tests::temp_db().await;
+        let addr = crate::api::tests::serve(crate::api::tests::state(cfg, db.clone())).await;
+        let base = format!("http://{addr}");
+
+        let c = reqwest::Client::new();
+        let release = format!("{base}/admin/quarantine/1/release");
+        let status = |r: reqwest::Response| r.status().as_u16();
+        assert_eq!(status(c.post(&release).send().await.unwrap()), 401);
+        assert_eq!(status(c.post(&release).bearer_auth("view-token").send().await.unwrap()), 403);
+        assert_eq!(status(c.post(&release).bearer_auth("ops-token").send().await.unwrap()), 404);
+        let list = c.get(format!("{base}/admin/quarantine")).bearer_auth("view-token").send().await.unwrap();
+        assert_eq!(status(list), 200);
+
+        let audit = crate::repo::list_audit(&db, None, 10).await.unwrap();
+        let seen: Vec<_> = audit.iter().map(|a| (a.token_name.as_deref(), a.status)).collect();
+        assert_eq!(seen, vec![(Some("viewer"), 200), (Some("ops"), 404), (Some("viewer"), 403), (None, 401)]);
+        assert_eq!(audit[1].path, "/admin/quarantine/1/release");
+        assert!(audit.iter().all(|a| a.remote_addr.is_some()));
+    }
+}
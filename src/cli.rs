@@ -0,0 +1,84 @@
+use anyhow::{anyhow, Result};
+
+use crate::config::Cfg;
+use crate::pause::{self, PauseState};
+
+pub const USAGE: &str = "usage: bridge-relayer [pause [--route NAME] [--reason TEXT] | resume [--route NAME] | pause-status]";
+
+/// One-shot operator commands. They work on the database directly, so they
+/// still apply when the relayer's API is unreachable.
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    Pause { route: Option<String>, reason: Option<String> },
+    Resume { route: Option<String> },
+    PauseStatus,
+}
+
+impl Command {
+    /// `None` without arguments, meaning: run the relayer.
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Option<Self>, String> {
+        let mut args = args.into_iter();
+        let Some(cmd) = args.next() else {
+            return Ok(None);
+        };
+        let (mut route, mut reason) = (None, None);
+        while let Some(flag) = args.next() {
+            let slot = match flag.as_str() {
+                "--route" => &mut route,
+                "--reason" if cmd == "pause" => &mut reason,
+                _ => return Err(format!("unexpected argument {flag:?}\n{USAGE}")),
+            };
+            *slot = Some(args.next().ok_or_else(|| format!("{flag} needs a value\n{USAGE}"))?);
+        }
+        match cmd.as_str() {
+            "pause" => Ok(Some(Command::Pause { route, reason })),
+            "resume" => Ok(Some(Command::Resume { route })),
+            "pause-status" if route.is_none() => Ok(Some(Command::PauseStatus)),
+            _ => Err(USAGE.into()),
+        }
+    }
+}
+
+/// Run `cmd` and print the resulting pause state as JSON. The running
+/// relayer sees the change on its next check and announces it on the event
+/// stream.
+pub async fn run(cfg: &Cfg, cmd: Command) -> Result<()> {
+    let db = crate::repo::init_db(&cfg.db_url).await?;
+    let by = format!("cli:{}", std::env::var("USER").unwrap_or_else(|_| "unknown".into()));
+    let state = match cmd {
+        Command::Pause { route, reason } => {
+            let target = pause::target(cfg, route.as_deref()).map_err(|e| anyhow!(e))?;
+            pause::set(&db, target, true, reason.as_deref(), &by).await?
+        }
+        Command::Resume { route } => {
+            let target = pause::target(cfg, route.as_deref()).map_err(|e| anyhow!(e))?;
+            pause::set(&db, target, false, None, &by).await?
+        }
+        Command::PauseStatus => PauseState::load(&db).await?,
+    };
+    println!("{}", serde_json::to_string_pretty(&state)?);
+    db.close().await;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Option<Command>, String> {
+        Command::parse(args.iter().map(|a| a.to_string()))
+    }
+
+    #[test]
+    fn parses_pause_commands() {
+        assert_eq!(parse(&[]), Ok(None));
+        assert_eq!(
+            parse(&["pause", "--route", "USDT", "--reason", "incident 12"]),
+            Ok(Some(Command::Pause { route: Some("USDT".into()), reason: Some("incident 12".into()) }))
+        );
+        assert_eq!(parse(&["resume"]), Ok(Some(Command::Resume { route: None })));
+        assert!(parse(&["resume", "--reason", "x"]).is_err());
+        assert!(parse(&["pause", "--route"]).is_err());
+        assert!(parse(&["serve"]).is_err());
+    }
+}
@@ -1,59 +1,990 @@
 use anyhow::{anyhow, Result};
-use serde::{Deserialize, Serialize};
-use base64::Engine; // <-- needed for .decode(...)
+use reqwest::Url;
+use serde::Deserialize;
+use solana_sdk::pubkey::Pubkey;
+use std::{net::SocketAddr, path::Path, str::FromStr, time::Duration};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+use crate::auth::{AdminToken, Scope};
+use crate::ratelimit::RateLimit;
+use crate::attestations::{CfgPreimageV1, RoutePreimageV1, CHAIN_SOLANA, CHAIN_TON};
+use crate::routes::{Route, RouteTable};
+use crate::supervisor::{Restart, RestartPolicy};
+use crate::ton::address::TonAddress;
+
+/// Fully validated relayer configuration.
+///
+/// Built once at startup by [`Cfg::load`] from (lowest to highest precedence)
+/// built-in defaults, the TOML config file and environment variables, then
+/// passed explicitly to every subsystem.
+#[derive(Debug, Clone)]
 pub struct Cfg {
     // Solana
-    pub sol_rpc_http: String,
-    pub sol_rpc_ws: String,
-    pub sol_bridge_program: String,
+    pub sol_rpc_http: Url,
+    pub sol_rpc_ws: Url,
+    pub sol_bridge_program: Pubkey,
+    pub sol_cluster: String,
 
     // TON
-    pub ton_api_base: String,
+    pub ton_api_base: Url,
     pub ton_api_key: Option<String>,
-    pub ton_watch_addr_b64: String,
+    pub ton_bridge_addr: TonAddress,
+    pub ton_global_id: i32,
+    pub ton_poll_interval: Duration,
+
+    // Bridge
+    pub routes: RouteTable,
+    pub dst_sol_pubkey: Pubkey,
+    pub signers: Vec<[u8; 32]>,
+    pub signer_threshold: u8,
 
     // Relayer signer
     pub relayer_sk_base64: String,
     pub relayer_pubkey: [u8; 32],
 
-    // misc
-    pub cfg_hash_hex: String,
+    // API / storage
+    pub api_bind: SocketAddr,
+    pub db_url: String,
+    /// How long `/events` history is kept for `Last-Event-ID` replay.
+    pub event_retention: Duration,
+    /// Bearer tokens for `/admin`; the admin API is closed when empty.
+    pub admin_tokens: Vec<AdminToken>,
+    /// Browser origins allowed by CORS; no CORS headers when empty.
+    pub api_cors_origins: Vec<String>,
+    pub api_max_body_bytes: usize,
+    pub api_request_timeout: Duration,
+    /// Per-client budget on the public routes; unlimited when `None`.
+    pub api_rate_limit: Option<RateLimit>,
+    /// Header carrying the client address when behind a proxy.
+    pub api_client_ip_header: Option<String>,
+
+    // Worker supervision
+    pub worker_restart: Restart,
+    pub worker_initial_backoff: Duration,
+    pub worker_max_backoff: Duration,
+    pub worker_max_restarts: Option<u32>,
+    /// How long shutdown waits for workers and the HTTP server to drain.
+    pub shutdown_grace: Duration,
+
+    // Readiness thresholds
+    pub health_ton_max_lag: Duration,
+    pub health_sol_max_slot_lag: u64,
+    pub health_check_timeout: Duration,
+
+    // Webhook delivery
+    /// Attempts before a delivery is moved to the dead-letter table.
+    pub webhook_max_attempts: u32,
+    pub webhook_initial_backoff: Duration,
+    pub webhook_max_backoff: Duration,
+    pub webhook_timeout: Duration,
+
+    // Telemetry
+    pub log_format: LogFormat,
+    /// OTLP/gRPC collector; spans are only exported when set.
+    pub otlp_endpoint: Option<Url>,
+    pub otel_service_name: String,
+
+    /// Derived from [`Cfg::cfg_preimage`], never taken from user input.
+    pub cfg_hash: [u8; 32],
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogFormat {
+    /// Human-readable, ANSI coloured.
+    Text,
+    /// One JSON object per line, including the active span fields.
+    Json,
+}
+
+const SOL_CLUSTERS: &[&str] = &["mainnet-beta", "testnet", "devnet", "localnet"];
+
+const DEFAULT_CONFIG_PATH: &str = "relayer.toml";
+
+/// On-disk layout of `relayer.toml`. Every field is optional here; required
+/// ones are enforced during validation so that all problems surface at once.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct FileCfg {
+    solana: SolanaSection,
+    ton: TonSection,
+    bridge: BridgeSection,
+    relayer: RelayerSection,
+    api: ApiSection,
+    admin: AdminSection,
+    db: DbSection,
+    supervisor: SupervisorSection,
+    health: HealthSection,
+    webhooks: WebhooksSection,
+    telemetry: TelemetrySection,
+    routes: Vec<RouteSection>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct SolanaSection {
+    rpc_http: Option<String>,
+    rpc_ws: Option<String>,
+    bridge_program: Option<String>,
+    cluster: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct TonSection {
+    api_base: Option<String>,
+    api_key: Option<String>,
+    bridge_addr: Option<String>,
+    global_id: Option<i32>,
+    poll_interval: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct BridgeSection {
+    dst_sol_pubkey: Option<String>,
+    signers: Option<Vec<String>>,
+    threshold: Option<u8>,
+    /// Optional: if set, must equal the hash derived from the config.
+    cfg_hash_hex: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct SupervisorSection {
+    /// `always`, `on_failure` or `never`.
+    restart: Option<String>,
+    initial_backoff: Option<String>,
+    max_backoff: Option<String>,
+    /// Consecutive failures before a worker is given up on; 0 = never.
+    max_restarts: Option<u32>,
+    shutdown_grace: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct HealthSection {
+    /// Max age of the last successful TON poll relative to the chain tip.
+    ton_max_lag: Option<String>,
+    /// Max slots the Solana RPC node may trail the cluster.
+    sol_max_slot_lag: Option<u64>,
+    check_timeout: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct WebhooksSection {
+    max_attempts: Option<u32>,
+    initial_backoff: Option<String>,
+    max_backoff: Option<String>,
+    timeout: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct TelemetrySection {
+    log_format: Option<String>,
+    otlp_endpoint: Option<String>,
+    service_name: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RouteSection {
+    #[serde(default)]
+    name: Option<String>,
+    ton_master: String,
+    sol_mint: String,
+    decimals_ton: u8,
+    decimals_sol: u8,
+    #[serde(default)]
+    min_amount: Option<RawAmount>,
+    #[serde(default)]
+    max_amount: Option<RawAmount>,
+    #[serde(default)]
+    allow_dust: bool,
+    #[serde(default)]
+    require_payload: bool,
+    #[serde(default = "default_true")]
+    enabled: bool,
+}
+
+/// TOML integers stop at i64, so large raw amounts may be given as strings.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum RawAmount {
+    Int(u64),
+    Str(String),
+}
+
+impl RawAmount {
+    fn parse(&self) -> Result<u128, String> {
+        match self {
+            RawAmount::Int(n) => Ok(*n as u128),
+            RawAmount::Str(s) => s.trim().parse().map_err(|e| format!("invalid amount {s:?}: {e}")),
+        }
+    }
+}
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct RelayerSection {
+    sk_base64: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct ApiSection {
+    bind: Option<String>,
+    event_retention: Option<String>,
+    cors_origins: Option<Vec<String>>,
+    max_body_bytes: Option<usize>,
+    request_timeout: Option<String>,
+    /// Requests per minute per client; 0 disables the limit.
+    rate_limit: Option<u32>,
+    rate_limit_burst: Option<u32>,
+    client_ip_header: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct AdminSection {
+    tokens: Vec<AdminTokenSection>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct AdminTokenSection {
+    name: String,
+    scope: String,
+    /// Hex SHA-256 of the token.
+    sha256: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct DbSection {
+    url: Option<String>,
+}
+
+impl FileCfg {
+    fn read(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| anyhow!("read config {}: {e}", path.display()))?;
+        toml::from_str(&text).map_err(|e| anyhow!("parse config {}: {e}", path.display()))
+    }
+
+    /// Environment variables win over the file.
+    fn apply_env(&mut self, errors: &mut Vec<String>) {
+        env_override(&mut self.solana.rpc_http, "SOL_RPC_HTTP");
+        env_override(&mut self.solana.rpc_ws, "SOL_RPC_WS");
+        env_override(&mut self.solana.bridge_program, "SOL_BRIDGE_PROGRAM");
+        env_override(&mut self.solana.cluster, "SOL_CLUSTER");
+
+        env_override(&mut self.ton.api_base, "TON_API_BASE");
+        env_override(&mut self.ton.api_key, "TONCENTER_API_KEY");
+        env_override(&mut self.ton.bridge_addr, "TON_BRIDGE_ADDR_B64");
+        env_override(&mut self.ton.poll_interval, "TON_POLL_INTERVAL");
+        env_parse(&mut self.ton.global_id, "TON_GLOBAL_ID", errors);
+
+        env_override(&mut self.bridge.dst_sol_pubkey, "DST_SOL_PUBKEY_BASE58");
+        env_override(&mut self.bridge.cfg_hash_hex, "CFG_HASH_HEX");
+        for legacy in ["TON_WATCH_ADDR_B64", "JETTON_DECIMALS"] {
+            if std::env::var_os(legacy).is_some() {
+                errors.push(format!("{legacy}: no longer supported, declare the jetton in a [[routes]] entry"));
+            }
+        }
+        env_parse(&mut self.bridge.threshold, "BRIDGE_THRESHOLD", errors);
+        if let Ok(s) = std::env::var("BRIDGE_SIGNERS") {
+            self.bridge.signers = Some(s.split(',').map(|k| k.trim().to_string()).collect());
+        }
+
+        env_override(&mut self.relayer.sk_base64, "RELAYER_SK_BASE64");
+        env_override(&mut self.api.bind, "API_BIND");
+        env_override(&mut self.api.event_retention, "API_EVENT_RETENTION");
+        if let Ok(s) = std::env::var("API_CORS_ORIGINS") {
+            self.api.cors_origins = Some(s.split(',').map(|o| o.trim().to_string()).filter(|o| !o.is_empty()).collect());
+        }
+        env_parse(&mut self.api.max_body_bytes, "API_MAX_BODY_BYTES", errors);
+        env_override(&mut self.api.request_timeout, "API_REQUEST_TIMEOUT");
+        env_parse(&mut self.api.rate_limit, "API_RATE_LIMIT", errors);
+        env_parse(&mut self.api.rate_limit_burst, "API_RATE_LIMIT_BURST", errors);
+        env_override(&mut self.api.client_ip_header, "API_CLIENT_IP_HEADER");
+        // name:scope:sha256hex, comma-separated; replaces [[admin.tokens]].
+        if let Ok(s) = std::env::var("ADMIN_TOKENS") {
+            self.admin.tokens.clear();
+            for entry in s.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+                match entry.split(':').collect::<Vec<_>>()[..] {
+                    [name, scope, sha256] => self.admin.tokens.push(AdminTokenSection {
+                        name: name.into(),
+                        scope: scope.into(),
+                        sha256: sha256.into(),
+                    }),
+                    _ => errors.push(format!("ADMIN_TOKENS: {entry:?} is not name:scope:sha256")),
+                }
+            }
+        }
+        env_override(&mut self.db.url, "DATABASE_URL");
+
+        env_override(&mut self.supervisor.restart, "WORKER_RESTART");
+        env_override(&mut self.supervisor.initial_backoff, "WORKER_INITIAL_BACKOFF");
+        env_override(&mut self.supervisor.max_backoff, "WORKER_MAX_BACKOFF");
+        env_parse(&mut self.supervisor.max_restarts, "WORKER_MAX_RESTARTS", errors);
+        env_override(&mut self.supervisor.shutdown_grace, "SHUTDOWN_GRACE");
+
+        env_override(&mut self.health.ton_max_lag, "HEALTH_TON_MAX_LAG");
+        env_parse(&mut self.health.sol_max_slot_lag, "HEALTH_SOL_MAX_SLOT_LAG", errors);
+        env_override(&mut self.health.check_timeout, "HEALTH_CHECK_TIMEOUT");
+
+        env_parse(&mut self.webhooks.max_attempts, "WEBHOOK_MAX_ATTEMPTS", errors);
+        env_override(&mut self.webhooks.initial_backoff, "WEBHOOK_INITIAL_BACKOFF");
+        env_override(&mut self.webhooks.max_backoff, "WEBHOOK_MAX_BACKOFF");
+        env_override(&mut self.webhooks.timeout, "WEBHOOK_TIMEOUT");
+
+        env_override(&mut self.telemetry.log_format, "LOG_FORMAT");
+        env_override(&mut self.telemetry.otlp_endpoint, "OTEL_EXPORTER_OTLP_ENDPOINT");
+        env_override(&mut self.telemetry.service_name, "OTEL_SERVICE_NAME");
+    }
+}
+
+fn env_override(slot: &mut Option<String>, var: &str) {
+    if let Ok(v) = std::env::var(var) {
+        *slot = Some(v);
+    }
+}
+
+fn env_parse<T: FromStr>(slot: &mut Option<T>, var: &str, errors: &mut Vec<String>)
+where
+    T::Err: std::fmt::Display,
+{
+    if let Ok(s) = std::env::var(var) {
+        match s.trim().parse() {
+            Ok(v) => *slot = Some(v),
+            Err(e) => errors.push(format!("{var}: {e}")),
+        }
+    }
+}
+
+/// Collects validation failures instead of stopping at the first one.
+#[derive(Default)]
+struct Validator {
+    errors: Vec<String>,
+}
+
+impl Validator {
+    fn required<T>(
+        &mut self,
+        key: &str,
+        value: Option<String>,
+        parse: impl FnOnce(&str) -> Result<T, String>,
+    ) -> Option<T> {
+        match value.as_deref().map(str::trim) {
+            None | Some("") => {
+                self.errors.push(format!("{key}: missing"));
+                None
+            }
+            Some(s) => self.check(key, parse(s)),
+        }
+    }
+
+    fn optional<T>(
+        &mut self,
+        key: &str,
+        value: Option<String>,
+        default: &str,
+        parse: impl FnOnce(&str) -> Result<T, String>,
+    ) -> Option<T> {
+        let s = value.filter(|s| !s.trim().is_empty());
+        let s = s.as_deref().map(str::trim).unwrap_or(default);
+        self.check(key, parse(s))
+    }
+
+    fn check<T>(&mut self, key: &str, res: Result<T, String>) -> Option<T> {
+        res.map_err(|e| self.errors.push(format!("{key}: {e}"))).ok()
+    }
+}
+
+fn url_with_scheme(schemes: &'static [&'static str]) -> impl Fn(&str) -> Result<Url, String> {
+    move |s| {
+        let url = Url::parse(s).map_err(|e| format!("invalid URL {s:?}: {e}"))?;
+        if !schemes.contains(&url.scheme()) {
+            return Err(format!("scheme must be one of {schemes:?}, got {:?}", url.scheme()));
+        }
+        Ok(url)
+    }
+}
+
+fn pubkey(s: &str) -> Result<Pubkey, String> {
+    Pubkey::from_str(s).map_err(|e| format!("invalid base58 pubkey {s:?}: {e}"))
+}
+
+fn ton_address(s: &str) -> Result<TonAddress, String> {
+    s.parse().map_err(|e| format!("invalid TON address {s:?}: {e}"))
+}
+
+fn relayer_pubkey(sk_b64: &str) -> Result<[u8; 32], String> {
+    let kp = crate::signer::load_keypair_base64(sk_b64).map_err(|e| format!("{e:#}"))?;
+    Ok(kp.public.to_bytes())
+}
+
+fn validate_routes(raw: Vec<RouteSection>, v: &mut Validator) -> RouteTable {
+    if raw.is_empty() {
+        v.errors.push("routes: at least one [[routes]] entry is required".into());
+        return RouteTable::default();
+    }
+    let mut routes = Vec::with_capacity(raw.len());
+    for (i, r) in raw.into_iter().enumerate() {
+        let key = |field: &str| format!("routes[{i}].{field}");
+        let ton_master = v.check(&key("ton_master"), ton_address(&r.ton_master));
+        let sol_mint = v.check(&key("sol_mint"), pubkey(&r.sol_mint));
+        for (field, d) in [("decimals_ton", r.decimals_ton), ("decimals_sol", r.decimals_sol)] {
+            if d > 38 {
+                v.errors.push(format!("{}: {d} exceeds 38", key(field)));
+            }
+        }
+        if let Some(name) = &r.name {
+            if name.trim().is_empty() || name == crate::pause::GLOBAL {
+                v.errors.push(format!("{}: {name:?} is reserved", key("name")));
+            }
+        }
+        let min_amount_raw = match &r.min_amount {
+            Some(a) => v.check(&key("min_amount"), a.parse()),
+            None => Some(0),
+        };
+        let max_amount_raw = match &r.max_amount {
+            Some(a) => v.check(&key("max_amount"), a.parse()).map(Some),
+            None => Some(None),
+        };
+        if let (Some(min), Some(Some(max))) = (min_amount_raw, max_amount_raw) {
+            if min > max {
+                v.errors.push(format!("{}: min_amount {min} exceeds max_amount {max}", key("min_amount")));
+            }
+        }
+        if let (Some(ton_master), Some(sol_mint), Some(min_amount_raw), Some(max_amount_raw)) =
+            (ton_master, sol_mint, min_amount_raw, max_amount_raw)
+        {
+            routes.push(Route {
+                name: r.name.unwrap_or_else(|| ton_master.to_raw()),
+                ton_master,
+                sol_mint,
+                decimals_ton: r.decimals_ton,
+                decimals_sol: r.decimals_sol,
+                min_amount_raw,
+                max_amount_raw,
+                allow_dust: r.allow_dust,
+                require_payload: r.require_payload,
+                enabled: r.enabled,
+            });
+        }
+    }
+    RouteTable::new(routes).unwrap_or_else(|e| {
+        v.errors.push(format!("routes: {e}"));
+        RouteTable::default()
+    })
 }
 
 impl Cfg {
-    pub fn from_env() -> Result<Self> {
-        let sol_rpc_http = std::env::var("SOL_RPC_HTTP")?;
-        let sol_rpc_ws = std::env::var("SOL_RPC_WS")?;
-        let sol_bridge_program = std::env::var("SOL_BRIDGE_PROGRAM")?;
+    /// Load `relayer.toml` (or the file named by `RELAYER_CONFIG`), apply env
+    /// overrides and validate. All validation errors are reported together.
+    pub fn load() -> Result<Self> {
+        let explicit = std::env::var("RELAYER_CONFIG").ok();
+        let path = explicit.as_deref().unwrap_or(DEFAULT_CONFIG_PATH);
+
+        let mut file = if explicit.is_some() || Path::new(path).exists() {
+            FileCfg::read(Path::new(path))?
+        } else {
+            FileCfg::default()
+        };
+
+        let mut v = Validator::default();
+        file.apply_env(&mut v.errors);
+        Self::validate(file, v)
+    }
+
+    fn validate(f: FileCfg, mut v: Validator) -> Result<Self> {
+        let http = url_with_scheme(&["http", "https"]);
+        let ws = url_with_scheme(&["ws", "wss"]);
+
+        let sol_rpc_http = v.required("solana.rpc_http (SOL_RPC_HTTP)", f.solana.rpc_http, &http);
+        let sol_rpc_ws = v.required("solana.rpc_ws (SOL_RPC_WS)", f.solana.rpc_ws, &ws);
+        let sol_bridge_program =
+            v.required("solana.bridge_program (SOL_BRIDGE_PROGRAM)", f.solana.bridge_program, pubkey);
+        let sol_cluster = v.optional("solana.cluster (SOL_CLUSTER)", f.solana.cluster, "devnet", |s| {
+            if SOL_CLUSTERS.contains(&s) { Ok(s.to_string()) } else { Err(format!("must be one of {SOL_CLUSTERS:?}")) }
+        });
+
+        let ton_api_base = v.optional(
+            "ton.api_base (TON_API_BASE)",
+            f.ton.api_base,
+            "https://testnet.toncenter.com/api/v3",
+            &http,
+        );
+        let ton_api_key = f.ton.api_key.filter(|k| !k.trim().is_empty());
+        let ton_bridge_addr = v.required("ton.bridge_addr (TON_BRIDGE_ADDR_B64)", f.ton.bridge_addr, ton_address);
+        let ton_global_id = f.ton.global_id.unwrap_or(-3);
+        let ton_poll_interval =
+            v.optional("ton.poll_interval (TON_POLL_INTERVAL)", f.ton.poll_interval, "5s", crate::utils::parse_duration);
+
+        let dst_sol_pubkey =
+            v.required("bridge.dst_sol_pubkey (DST_SOL_PUBKEY_BASE58)", f.bridge.dst_sol_pubkey, pubkey);
+        let routes = validate_routes(f.routes, &mut v);
+        let expected_cfg_hash = f
+            .bridge
+            .cfg_hash_hex
+            .filter(|s| !s.trim().is_empty())
+            .and_then(|s| v.check("bridge.cfg_hash_hex (CFG_HASH_HEX)", crate::utils::hex32(&s).map_err(|e| e.to_string())));
+
+        let relayer_sk_base64 = f.relayer.sk_base64.unwrap_or_default();
+        let relayer_pubkey =
+            v.required("relayer.sk_base64 (RELAYER_SK_BASE64)", Some(relayer_sk_base64.clone()), relayer_pubkey);
+
+        let mut signers = Vec::new();
+        for s in f.bridge.signers.unwrap_or_default() {
+            if let Some(k) = v.check("bridge.signers (BRIDGE_SIGNERS)", crate::utils::hex32(&s).map_err(|e| format!("{s:?}: {e}"))) {
+                signers.push(k);
+            }
+        }
+        if signers.is_empty() {
+            signers.extend(relayer_pubkey);
+        }
+        signers.sort();
+        signers.dedup();
+        if let Some(pk) = relayer_pubkey {
+            if !signers.contains(&pk) {
+                v.errors.push(format!("bridge.signers (BRIDGE_SIGNERS): relayer key {} is not in the signer set", hex::encode(pk)));
+            }
+        }
+        let signer_threshold = f.bridge.threshold.unwrap_or(1);
+        if signer_threshold == 0 || signer_threshold as usize > signers.len() {
+            v.errors.push(format!(
+                "bridge.threshold (BRIDGE_THRESHOLD): {signer_threshold} must be between 1 and {} signers",
+                signers.len()
+            ));
+        }
 
-        let ton_api_base = std::env::var("TON_API_BASE")?;
-        let ton_api_key = std::env::var("TONCENTER_API_KEY").ok();
-        let ton_watch_addr_b64 = std::env::var("TON_WATCH_ADDR_B64")?;
+        let api_bind = v.optional("api.bind (API_BIND)", f.api.bind, "127.0.0.1:3000", |s| {
+            s.parse::<SocketAddr>().map_err(|e| format!("invalid socket address {s:?}: {e}"))
+        });
+        let db_url = v.optional("db.url (DATABASE_URL)", f.db.url, "sqlite://relayer.db", |s| {
+            if s.starts_with("sqlite:") { Ok(s.to_string()) } else { Err("only sqlite: URLs are supported".into()) }
+        });
+        let event_retention = v.optional(
+            "api.event_retention (API_EVENT_RETENTION)",
+            f.api.event_retention,
+            "168h",
+            crate::utils::parse_duration,
+        );
 
-        let relayer_sk_base64 = std::env::var("RELAYER_SK_BASE64")?;
-        let kp = base64::engine::general_purpose::STANDARD
-            .decode(relayer_sk_base64.as_bytes())
-            .map_err(|e| anyhow!("RELAYER_SK_BASE64 decode: {e}"))?;
-        if kp.len() != 64 {
-            return Err(anyhow!("RELAYER_SK_BASE64 must be 64 bytes (ed25519 keypair)"));
+        let mut api_cors_origins = Vec::new();
+        for o in f.api.cors_origins.unwrap_or_default() {
+            // An origin is scheme://host[:port], exactly as browsers send it.
+            let parsed = http(&o).map(|u| u.origin().ascii_serialization());
+            match parsed {
+                Ok(origin) if origin == o.trim_end_matches('/') => api_cors_origins.push(origin),
+                Ok(origin) => v.errors.push(format!("api.cors_origins (API_CORS_ORIGINS): {o:?} is not an origin, use {origin:?}")),
+                Err(e) => v.errors.push(format!("api.cors_origins (API_CORS_ORIGINS): {e}")),
+            }
+        }
+        let api_max_body_bytes = f.api.max_body_bytes.unwrap_or(64 * 1024);
+        let api_request_timeout = v.optional(
+            "api.request_timeout (API_REQUEST_TIMEOUT)",
+            f.api.request_timeout,
+            "30s",
+            crate::utils::parse_duration,
+        );
+        let api_rate_limit = match f.api.rate_limit.unwrap_or(600) {
+            0 => None,
+            per_minute => Some(RateLimit { per_minute, burst: f.api.rate_limit_burst.unwrap_or(60).max(1) }),
+        };
+        let api_client_ip_header = f
+            .api
+            .client_ip_header
+            .map(|h| h.trim().to_ascii_lowercase())
+            .filter(|h| !h.is_empty());
+        if let Some(h) = &api_client_ip_header {
+            if axum::http::HeaderName::from_bytes(h.as_bytes()).is_err() {
+                v.errors.push(format!("api.client_ip_header (API_CLIENT_IP_HEADER): {h:?} is not a header name"));
+            }
         }
-        let mut pubkey = [0u8; 32];
-        pubkey.copy_from_slice(&kp[32..]);
 
-        let cfg_hash_hex = std::env::var("CFG_HASH_HEX")?;
+        let mut admin_tokens: Vec<AdminToken> = Vec::new();
+        for (i, t) in f.admin.tokens.iter().enumerate() {
+            let key = format!("admin.tokens[{i}] (ADMIN_TOKENS)");
+            let name = t.name.trim();
+            if name.is_empty() || admin_tokens.iter().any(|a| a.name == name) {
+                v.check::<()>(&key, Err(format!("name {name:?} must be non-empty and unique")));
+                continue;
+            }
+            let scope = v.check(&key, t.scope.parse::<Scope>());
+            let sha256 = v.check(&key, crate::utils::hex32(t.sha256.trim()).map_err(|e| format!("sha256: {e}")));
+            if let (Some(scope), Some(sha256)) = (scope, sha256) {
+                admin_tokens.push(AdminToken { name: name.to_string(), scope, sha256 });
+            }
+        }
+
+        let worker_restart =
+            v.optional("supervisor.restart (WORKER_RESTART)", f.supervisor.restart, "always", str::parse::<Restart>);
+        let worker_initial_backoff = v.optional(
+            "supervisor.initial_backoff (WORKER_INITIAL_BACKOFF)",
+            f.supervisor.initial_backoff,
+            "1s",
+            crate::utils::parse_duration,
+        );
+        let worker_max_backoff = v.optional(
+            "supervisor.max_backoff (WORKER_MAX_BACKOFF)",
+            f.supervisor.max_backoff,
+            "60s",
+            crate::utils::parse_duration,
+        );
+        let worker_max_restarts = f.supervisor.max_restarts.filter(|&n| n > 0);
+        let shutdown_grace = v.optional(
+            "supervisor.shutdown_grace (SHUTDOWN_GRACE)",
+            f.supervisor.shutdown_grace,
+            "30s",
+            crate::utils::parse_duration,
+        );
+
+        let health_ton_max_lag = v.optional(
+            "health.ton_max_lag (HEALTH_TON_MAX_LAG)",
+            f.health.ton_max_lag,
+            "2m",
+            crate::utils::parse_duration,
+        );
+        let health_sol_max_slot_lag = f.health.sol_max_slot_lag.unwrap_or(150);
+        let health_check_timeout = v.optional(
+            "health.check_timeout (HEALTH_CHECK_TIMEOUT)",
+            f.health.check_timeout,
+            "5s",
+            crate::utils::parse_duration,
+        );
 
-        Ok(Self {
+        let webhook_max_attempts = match f.webhooks.max_attempts.unwrap_or(8) {
+            0 => v.check("webhooks.max_attempts (WEBHOOK_MAX_ATTEMPTS)", Err("must be at least 1".into())),
+            n => Some(n),
+        };
+        let webhook_initial_backoff = v.optional(
+            "webhooks.initial_backoff (WEBHOOK_INITIAL_BACKOFF)",
+            f.webhooks.initial_backoff,
+            "5s",
+            crate::utils::parse_duration,
+        );
+        let webhook_max_backoff = v.optional(
+            "webhooks.max_backoff (WEBHOOK_MAX_BACKOFF)",
+            f.webhooks.max_backoff,
+            "1h",
+            crate::utils::parse_duration,
+        );
+        let webhook_timeout = v.optional(
+            "webhooks.timeout (WEBHOOK_TIMEOUT)",
+            f.webhooks.timeout,
+            "10s",
+            crate::utils::parse_duration,
+        );
+
+        let log_format = v.optional("telemetry.log_format (LOG_FORMAT)", f.telemetry.log_format, "text", |s| {
+            match s {
+                "text" => Ok(LogFormat::Text),
+                "json" => Ok(LogFormat::Json),
+                _ => Err("must be \"text\" or \"json\"".into()),
+            }
+        });
+        let otlp_endpoint = match f.telemetry.otlp_endpoint.filter(|s| !s.trim().is_empty()) {
+            Some(s) => v.check("telemetry.otlp_endpoint (OTEL_EXPORTER_OTLP_ENDPOINT)", http(s.trim())),
+            None => None,
+        };
+        let otel_service_name = f
+            .telemetry
+            .service_name
+            .filter(|s| !s.trim().is_empty())
+            .unwrap_or_else(|| "bridge-relayer".into());
+
+        if !v.errors.is_empty() {
+            return Err(anyhow!("invalid configuration:\n  - {}", v.errors.join("\n  - ")));
+        }
+        let (
+            Some(sol_rpc_http),
+            Some(sol_rpc_ws),
+            Some(sol_bridge_program),
+            Some(sol_cluster),
+            Some(ton_api_base),
+            Some(ton_bridge_addr),
+            Some(ton_poll_interval),
+            Some(dst_sol_pubkey),
+            Some(relayer_pubkey),
+            Some(api_bind),
+            Some(db_url),
+            Some(event_retention),
+            Some(api_request_timeout),
+            Some(worker_restart),
+            Some(worker_initial_backoff),
+            Some(worker_max_backoff),
+            Some(shutdown_grace),
+            Some(health_ton_max_lag),
+            Some(health_check_timeout),
+            Some(webhook_max_attempts),
+            Some(webhook_initial_backoff),
+            Some(webhook_max_backoff),
+            Some(webhook_timeout),
+            Some(log_format),
+        ) = (
             sol_rpc_http,
             sol_rpc_ws,
             sol_bridge_program,
+            sol_cluster,
+            ton_api_base,
+            ton_bridge_addr,
+            ton_poll_interval,
+            dst_sol_pubkey,
+            relayer_pubkey,
+            api_bind,
+            db_url,
+            event_retention,
+            api_request_timeout,
+            worker_restart,
+            worker_initial_backoff,
+            worker_max_backoff,
+            shutdown_grace,
+            health_ton_max_lag,
+            health_check_timeout,
+            webhook_max_attempts,
+            webhook_initial_backoff,
+            webhook_max_backoff,
+            webhook_timeout,
+            log_format,
+        )
+        else {
+            unreachable!("every unset field records a validation error");
+        };
+
+        let mut cfg = Self {
+            sol_rpc_http,
+            sol_rpc_ws,
+            sol_bridge_program,
+            sol_cluster,
             ton_api_base,
             ton_api_key,
-            ton_watch_addr_b64,
+            ton_bridge_addr,
+            ton_global_id,
+            ton_poll_interval,
+            routes,
+            dst_sol_pubkey,
+            signers,
+            signer_threshold,
             relayer_sk_base64,
-            relayer_pubkey: pubkey,
-            cfg_hash_hex,
-        })
+            relayer_pubkey,
+            api_bind,
+            db_url,
+            event_retention,
+            admin_tokens,
+            api_cors_origins,
+            api_max_body_bytes,
+            api_request_timeout,
+            api_rate_limit,
+            api_client_ip_header,
+            worker_restart,
+            worker_initial_backoff,
+            worker_max_backoff,
+            worker_max_restarts,
+            shutdown_grace,
+            health_ton_max_lag,
+            health_sol_max_slot_lag,
+            health_check_timeout,
+            webhook_max_attempts,
+            webhook_initial_backoff,
+            webhook_max_backoff,
+            webhook_timeout,
+            log_format,
+            otlp_endpoint,
+            otel_service_name,
+            cfg_hash: [0u8; 32],
+        };
+        cfg.cfg_hash = cfg.cfg_preimage().hash();
+
+        if let Some(expected) = expected_cfg_hash {
+            if expected != cfg.cfg_hash {
+                return Err(anyhow!(
+                    "bridge.cfg_hash_hex (CFG_HASH_HEX) is {} but the configuration hashes to {}; \
+                     refusing to sign with a mismatched cfg_hash",
+                    hex::encode(expected),
+                    hex::encode(cfg.cfg_hash)
+                ));
+            }
+        }
+        Ok(cfg)
+    }
+
+    /// Restart policy for long-running workers.
+    pub fn restart_policy(&self) -> RestartPolicy {
+        RestartPolicy {
+            restart: self.worker_restart,
+            initial_backoff: self.worker_initial_backoff,
+            max_backoff: self.worker_max_backoff,
+            max_restarts: self.worker_max_restarts,
+        }
+    }
+
+    pub fn webhook_policy(&self) -> crate::webhooks::DeliveryPolicy {
+        crate::webhooks::DeliveryPolicy {
+            max_attempts: self.webhook_max_attempts,
+            initial_backoff: self.webhook_initial_backoff,
+            max_backoff: self.webhook_max_backoff,
+            timeout: self.webhook_timeout,
+        }
+    }
+
+    /// Canonical preimage of `cfg_hash`. Routes and signers are sorted so the
+    /// hash does not depend on the order they appear in the config file.
+    pub fn cfg_preimage(&self) -> CfgPreimageV1 {
+        // Limits and enable flags are relayer policy, not part of the bridge identity.
+        let routes = self
+            .routes
+            .iter()
+            .map(|r| RoutePreimageV1 {
+                jetton_minter_ton: r.ton_master.wc_hash36(),
+                decimals_ton: r.decimals_ton,
+                sol_mint: r.sol_mint.to_bytes(),
+                decimals_sol: r.decimals_sol,
+            })
+            .collect();
+        CfgPreimageV1 {
+            ver: 1,
+            src_chain: CHAIN_TON,
+            dst_chain: CHAIN_SOLANA,
+            ton_global_id: self.ton_global_id,
+            sol_cluster: self.sol_cluster.clone(),
+            ton_bridge: self.ton_bridge_addr.wc_hash36(),
+            sol_bridge_program: self.sol_bridge_program.to_bytes(),
+            routes,
+            signers: self.signers.clone(),
+            threshold: self.signer_threshold,
+        }
+    }
+
+    /// Whether TON friendly addresses should carry the testnet flag
+    /// (anything other than the mainnet global id -239).
+    pub fn ton_testnet(&self) -> bool {
+        self.ton_global_id != -239
+    }
+
+    /// `ton_api_base` without a trailing slash, ready for `format!("{}/path")`.
+    pub fn ton_api_base(&self) -> &str {
+        self.ton_api_base.as_str().trim_end_matches('/')
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_all_errors_at_once() {
+        let f: FileCfg = toml::from_str(
+            r#"
+            [solana]
+            rpc_http = "ftp://example.com"
+            bridge_program = "not-a-pubkey"
+
+            [ton]
+            poll_interval = "soon"
+
+            [telemetry]
+            log_format = "xml"
+
+            [[routes]]
+            ton_master = "0:zz"
+            sol_mint = "11111111111111111111111111111111"
+            decimals_ton = 9
+            decimals_sol = 6
+            min_amount = 10
+            max_amount = "5"
+            "#,
+        )
+        .unwrap();
+        let err = Cfg::validate(f, Validator::default()).unwrap_err().to_string();
+        for key in [
+            "solana.rpc_http",
+            "solana.rpc_ws",
+            "solana.bridge_program",
+            "ton.bridge_addr",
+            "routes[0].ton_master",
+            "routes[0].min_amount",
+            "ton.poll_interval",
+            "bridge.dst_sol_pubkey",
+            "relayer.sk_base64",
+            "telemetry.log_format",
+        ] {
+            assert!(err.contains(key), "missing {key} in {err}");
+        }
+    }
+
+    const MINIMAL: &str = r#"
+        [solana]
+        rpc_http = "https://api.devnet.solana.com"
+        rpc_ws = "wss://api.devnet.solana.com/"
+        bridge_program = "11111111111111111111111111111111"
+        [ton]
+        bridge_addr = "EQCD39VS5jcptHL8vMjEXrzGaRcCVYto7HUn4bpAOg8xqB2N"
+        [[routes]]
+        ton_master = "0:83DFD552E63729B472FCBCC8C45EBCC6691702558B68EC7527E1BA403A0F31A8"
+        sol_mint = "11111111111111111111111111111111"
+        decimals_ton = 9
+        decimals_sol = 6
+        [bridge]
+        dst_sol_pubkey = "11111111111111111111111111111111"
+        [relayer]
+        sk_base64 = "AQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQE="
+    "#;
+
+    /// A valid config with `sections` (TOML tables) appended.
+    pub(crate) fn cfg_with(sections: &str) -> Cfg {
+        let f: FileCfg = toml::from_str(&format!("{MINIMAL}\n{sections}")).unwrap();
+        Cfg::validate(f, Validator::default()).unwrap()
+    }
+
+    fn load_str(extra: &str) -> Result<Cfg> {
+        let f: FileCfg = toml::from_str(&MINIMAL.replace("[bridge]", &format!("[bridge]\n{extra}"))).unwrap();
+        Cfg::validate(f, Validator::default())
+    }
+
+    #[test]
+    fn cfg_hash_is_canonical_and_checked() {
+        let base = load_str("").unwrap();
+        let other = hex::encode([7u8; 32]);
+        let me = hex::encode(base.relayer_pubkey);
+
+        let a = load_str(&format!("signers = [\"{me}\", \"{other}\"]")).unwrap();
+        let b = load_str(&format!("signers = [\"{other}\", \"{me}\", \"{other}\"]")).unwrap();
+        assert_eq!(a.cfg_hash, b.cfg_hash);
+        assert_ne!(a.cfg_hash, base.cfg_hash);
+
+        let ok = format!("cfg_hash_hex = \"{}\"", hex::encode(base.cfg_hash));
+        assert!(load_str(&ok).is_ok());
+        let bad = format!("cfg_hash_hex = \"{}\"", hex::encode([0u8; 32]));
+        assert!(load_str(&bad).unwrap_err().to_string().contains("refusing"));
+    }
+
+    #[test]
+    fn rejects_duplicate_and_reserved_route_names() {
+        let route = |master: &str, name: &str| {
+            format!(
+                "[[routes]]\nname = \"{name}\"\nton_master = \"0:{}\"\nsol_mint = \"11111111111111111111111111111111\"\ndecimals_ton = 9\ndecimals_sol = 6\n",
+                master.repeat(32)
+            )
+        };
+        let load = |extra: String| {
+            let f: FileCfg = toml::from_str(&format!("{MINIMAL}\n{extra}")).unwrap();
+            Cfg::validate(f, Validator::default()).map_err(|e| e.to_string())
+        };
+        assert!(load(route("AA", "A") + &route("BB", "B")).is_ok());
+        assert!(load(route("AA", "A") + &route("BB", "A")).unwrap_err().contains("duplicate route name \"A\""));
+        assert!(load(route("AA", " ")).unwrap_err().contains("routes[1].name"));
+        assert!(load(route("AA", "*")).unwrap_err().contains("routes[1].name"));
+    }
+
+    #[test]
+    fn rejects_unknown_keys() {
+        assert!(toml::from_str::<FileCfg>("[ton]\nwatch_addr = \"x\"").is_err());
     }
 }
@@ -0,0 +1,238 @@
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use std::time::Duration;
+
+/// Error taxonomy shared by the watcher, ingest, storage and submit paths.
+///
+/// The variant decides what callers do next: retry later, back off for a
+/// provider, or stop and surface the failure.
+#[derive(Debug, thiserror::Error)]
+pub enum RelayerError {
+    /// Connection failures, timeouts and other transport problems.
+    #[error("network error: {0}")]
+    Network(String),
+
+    /// HTTP 429 (or equivalent) from an upstream provider.
+    #[error("rate limited by {provider}")]
+    RateLimited { provider: &'static str, retry_after: Option<Duration> },
+
+    /// Non-success HTTP status other than 429 from an upstream provider.
+    #[error("{provider} returned HTTP {status}: {body}")]
+    Provider { provider: &'static str, status: u16, body: String },
+
+    /// Data from a provider or the database could not be decoded.
+    #[error("decode error: {0}")]
+    Decode(String),
+
+    /// Something that must never happen did; retrying will not help.
+    #[error("invariant violated: {0}")]
+    Invariant(String),
+
+    #[error("storage error: {0}")]
+    Storage(#[from] sqlx::Error),
+
+    #[error("signing error: {0}")]
+    Signing(String),
+
+    /// The Solana program or RPC rejected a transaction.
+    #[error("rejected on-chain: {0}")]
+    Rejected(String),
+}
+
+pub type Result<T, E = RelayerError> = std::result::Result<T, E>;
+
+impl RelayerError {
+    /// Short machine-readable name, used in logs and API error bodies.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            RelayerError::Network(_) => "network",
+            RelayerError::RateLimited { .. } => "rate_limited",
+            RelayerError::Provider { .. } => "provider",
+            RelayerError::Decode(_) => "decode",
+            RelayerError::Invariant(_) => "invariant",
+            RelayerError::Storage(_) => "storage",
+            RelayerError::Signing(_) => "signing",
+            RelayerError::Rejected(_) => "rejected",
+        }
+    }
+
+    /// Whether the same operation may succeed if attempted again later.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            RelayerError::Network(_) | RelayerError::RateLimited { .. } => true,
+            RelayerError::Provider { status, .. } => *status >= 500,
+            RelayerError::Storage(e) => matches!(
+                e,
+                sqlx::Error::PoolTimedOut | sqlx::Error::Io(_) | sqlx::Error::WorkerCrashed
+            ) || e.as_database_error().is_some_and(|d| {
+                // SQLITE_BUSY / SQLITE_LOCKED
+                matches!(d.code().as_deref(), Some("5") | Some("6"))
+            }),
+            RelayerError::Decode(_)
+            | RelayerError::Invariant(_)
+            | RelayerError::Signing(_)
+            | RelayerError::Rejected(_) => false,
+        }
+    }
+
+    /// Provider-requested delay before the next attempt, if any.
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self {
+            RelayerError::RateLimited { retry_after, .. } => *retry_after,
+            _ => None,
+        }
+    }
+
+    pub fn status_code(&self) -> StatusCode {
+        match self {
+            RelayerError::Network(_) | RelayerError::Provider { .. } => StatusCode::BAD_GATEWAY,
+            RelayerError::RateLimited { .. } => StatusCode::SERVICE_UNAVAILABLE,
+            RelayerError::Decode(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            RelayerError::Rejected(_) => StatusCode::CONFLICT,
+            RelayerError::Storage(sqlx::Error::RowNotFound) => StatusCode::NOT_FOUND,
+            RelayerError::Invariant(_) | RelayerError::Storage(_) | RelayerError::Signing(_) => {
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
+        }
+    }
+
+    /// Classify a non-success provider response.
+    pub fn from_status(provider: &'static str, status: reqwest::StatusCode, retry_after: Option<&str>, body: &str) -> Self {
+        if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+            let retry_after = retry_after.and_then(|s| s.trim().parse().ok()).map(Duration::from_secs);
+            return RelayerError::RateLimited { provider, retry_after };
+        }
+        RelayerError::Provider { provider, status: status.as_u16(), body: body.chars().take(300).collect() }
+    }
+}
+
+impl From<reqwest::Error> for RelayerError {
+    fn from(e: reqwest::Error) -> Self {
+        if e.is_decode() {
+            RelayerError::Decode(e.to_string())
+        } else {
+            RelayerError::Network(e.to_string())
+        }
+    }
+}
+
+impl From<serde_json::Error> for RelayerError {
+    fn from(e: serde_json::Error) -> Self {
+        RelayerError::Decode(e.to_string())
+    }
+}
+
+tokio::task_local! {
+    /// Id of the HTTP request being served, set by the API's request
+    /// middleware so error bodies and logs can quote it.
+    pub static REQUEST_ID: String;
+}
+
+/// Error returned by API handlers. The body is
+/// `{"error": message, "code": code, "request_id": id}`; `code` is stable and
+/// meant for programs, `error` for people.
+#[derive(Debug)]
+pub struct ApiError {
+    pub status: StatusCode,
+    pub code: &'static str,
+    pub message: String,
+    /// The underlying failure: logged, never returned.
+    source: Option<String>,
+}
+
+impl ApiError {
+    pub fn new(status: StatusCode, message: impl std::fmt::Display) -> Self {
+        Self { status, code: code_for(status), message: message.to_string(), source: None }
+    }
+}
+
+/// Default `code` for a status without a more specific one.
+fn code_for(status: StatusCode) -> &'static str {
+    match status {
+        StatusCode::BAD_REQUEST => "bad_request",
+        StatusCode::UNAUTHORIZED => "unauthorized",
+        StatusCode::FORBIDDEN => "forbidden",
+        StatusCode::NOT_FOUND => "not_found",
+        StatusCode::METHOD_NOT_ALLOWED => "method_not_allowed",
+        StatusCode::CONFLICT => "conflict",
+        StatusCode::PAYLOAD_TOO_LARGE => "payload_too_large",
+        StatusCode::UNSUPPORTED_MEDIA_TYPE => "unsupported_media_type",
+        StatusCode::UNPROCESSABLE_ENTITY => "unprocessable",
+        StatusCode::TOO_MANY_REQUESTS => "rate_limited",
+        StatusCode::BAD_GATEWAY => "upstream",
+        StatusCode::SERVICE_UNAVAILABLE => "unavailable",
+        StatusCode::GATEWAY_TIMEOUT => "timeout",
+        s if s.is_client_error() => "bad_request",
+        _ => "internal",
+    }
+}
+
+impl From<RelayerError> for ApiError {
+    fn from(e: RelayerError) -> Self {
+        let status = e.status_code();
+        let code = match &e {
+            RelayerError::Storage(sqlx::Error::RowNotFound) => "not_found",
+            e => e.kind(),
+        };
+        if status == StatusCode::INTERNAL_SERVER_ERROR {
+            // Storage and signing errors say nothing useful to a client and
+            // may say too much; the log has them under the request id.
+            Self { status, code, message: "internal error".into(), source: Some(e.to_string()) }
+        } else {
+            Self { status, code, message: e.to_string(), source: None }
+        }
+    }
+}
+
+impl From<sqlx::Error> for ApiError {
+    fn from(e: sqlx::Error) -> Self {
+        RelayerError::from(e).into()
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let request_id = REQUEST_ID.try_with(Clone::clone).ok();
+        let id = request_id.as_deref().unwrap_or("-");
+        let detail = self.source.as_deref().unwrap_or(&self.message);
+        if self.status.is_server_error() {
+            tracing::error!(request_id = id, code = self.code, status = self.status.as_u16(), "{detail}");
+        } else {
+            tracing::debug!(request_id = id, code = self.code, status = self.status.as_u16(), "{detail}");
+        }
+        let body = serde_json::json!({ "error": self.message, "code": self.code, "request_id": request_id });
+        (self.status, Json(body)).into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn provider_statuses_are_classified() {
+        let e = RelayerError::from_status("toncenter", reqwest::StatusCode::TOO_MANY_REQUESTS, Some("3"), "");
+        assert!(e.is_retryable());
+        assert_eq!(e.retry_after(), Some(Duration::from_secs(3)));
+
+        let e = RelayerError::from_status("toncenter", reqwest::StatusCode::BAD_GATEWAY, None, "upstream");
+        assert!(e.is_retryable());
+        assert_eq!(e.status_code(), StatusCode::BAD_GATEWAY);
+
+        let e = RelayerError::from_status("toncenter", reqwest::StatusCode::UNAUTHORIZED, None, "bad key");
+        assert!(!e.is_retryable());
+        assert_eq!(e.kind(), "provider");
+    }
+
+    #[test]
+    fn api_errors_hide_internal_details() {
+        let e = ApiError::from(RelayerError::Storage(sqlx::Error::PoolTimedOut));
+        assert_eq!((e.status, e.code, e.message.as_str()), (StatusCode::INTERNAL_SERVER_ERROR, "storage", "internal error"));
+        let e = ApiError::from(RelayerError::Storage(sqlx::Error::RowNotFound));
+        assert_eq!((e.status, e.code), (StatusCode::NOT_FOUND, "not_found"));
+        let e = ApiError::from(RelayerError::Network("connection reset".into()));
+        assert_eq!((e.status, e.code, e.message.as_str()), (StatusCode::BAD_GATEWAY, "network", "network error: connection reset"));
+        assert_eq!(ApiError::new(StatusCode::CONFLICT, "held").code, "conflict");
+    }
+}
@@ -0,0 +1,316 @@
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast;
+use tokio_util::sync::CancellationToken;
+use utoipa::ToSchema;
+
+use crate::errors::{RelayerError, Result};
+
+/// Version of the JSON encoding below. Bump it when a field is removed,
+/// renamed or changes meaning; adding a field does not need a bump, and
+/// consumers are expected to ignore fields they do not know.
+pub const SCHEMA_VERSION: u16 = 1;
+
+/// Everything the relayer reports to stream, webhook and metrics consumers.
+///
+/// Encoded as one flat JSON object, `{"v":1,"type":"burn",...}`. Raw
+/// amounts are decimal strings since they may exceed 2^53.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RelayerEvent {
+    /// A new burn was stored.
+    Burn(BurnEvent),
+    /// A burn was signed and queued for submission.
+    Attestation(AttestationEvent),
+    /// A burn was held back instead of being signed.
+    Quarantine(QuarantineEvent),
+    /// A submission attempt reached a final outcome.
+    Submission(SubmissionEvent),
+    /// Relaying was paused or resumed, globally or for one route.
+    Pause(PauseEvent),
+    /// A submitted transaction was finalized on Solana.
+    Confirmation(ConfirmationEvent),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct BurnEvent {
+    pub route: String,
+    pub src_tx: String,
+    pub lt: u64,
+    pub utime: u64,
+    pub owner: String,
+    pub amount_raw: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct AttestationEvent {
+    pub kind: String,
+    pub route: String,
+    pub hash_hex: String,
+    pub src_tx: String,
+    pub lt: u64,
+    pub utime: u64,
+    pub owner: String,
+    /// Destination Solana account, base58.
+    pub dst: String,
+    pub amount_raw: String,
+    pub amount_dst: String,
+    pub dust_raw: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct QuarantineEvent {
+    pub route: String,
+    /// TON tx hash, or a content hash when the provider gave none.
+    pub key: String,
+    pub code: String,
+    pub reason: String,
+    pub releasable: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SubmissionStatus {
+    Submitted,
+    Failed,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct SubmissionEvent {
+    pub hash_hex: String,
+    pub src_tx: Option<String>,
+    pub owner: Option<String>,
+    /// Unknown when the stored payload no longer decodes.
+    pub route: Option<String>,
+    pub dst: Option<String>,
+    pub status: SubmissionStatus,
+    pub error: Option<String>,
+    pub sol_signature: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct PauseEvent {
+    /// `None` for the global switch.
+    pub route: Option<String>,
+    pub paused: bool,
+    pub reason: Option<String>,
+    /// Token name or CLI user that made the change.
+    pub by: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct ConfirmationEvent {
+    pub hash_hex: String,
+    pub sol_signature: String,
+    /// Slot the transaction was finalized in.
+    pub slot: u64,
+    pub src_tx: Option<String>,
+    pub owner: Option<String>,
+    /// Unknown when the stored payload no longer decodes.
+    pub route: Option<String>,
+    pub dst: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Envelope<E> {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    id: Option<i64>,
+    v: u16,
+    #[serde(flatten)]
+    event: E,
+}
+
+impl RelayerEvent {
+    /// Every `type` tag.
+    pub const NAMES: [&'static str; 6] = ["burn", "attestation", "quarantine", "submission", "pause", "confirmation"];
+
+    /// The `type` tag, also used as the SSE event name.
+    pub fn name(&self) -> &'static str {
+        match self {
+            RelayerEvent::Burn(_) => "burn",
+            RelayerEvent::Attestation(_) => "attestation",
+            RelayerEvent::Quarantine(_) => "quarantine",
+            RelayerEvent::Submission(_) => "submission",
+            RelayerEvent::Pause(_) => "pause",
+            RelayerEvent::Confirmation(_) => "confirmation",
+        }
+    }
+
+    /// TON owner (raw form) of the burn behind the event, when known.
+    pub fn owner(&self) -> Option<&str> {
+        match self {
+            RelayerEvent::Burn(e) => Some(&e.owner),
+            RelayerEvent::Attestation(e) => Some(&e.owner),
+            RelayerEvent::Submission(e) => e.owner.as_deref(),
+            RelayerEvent::Confirmation(e) => e.owner.as_deref(),
+            RelayerEvent::Quarantine(_) | RelayerEvent::Pause(_) => None,
+        }
+    }
+
+    /// Destination Solana account (base58), when known.
+    pub fn dst(&self) -> Option<&str> {
+        match self {
+            RelayerEvent::Attestation(e) => Some(&e.dst),
+            RelayerEvent::Submission(e) => e.dst.as_deref(),
+            RelayerEvent::Confirmation(e) => e.dst.as_deref(),
+            RelayerEvent::Burn(_) | RelayerEvent::Quarantine(_) | RelayerEvent::Pause(_) => None,
+        }
+    }
+
+    pub fn route(&self) -> Option<&str> {
+        match self {
+            RelayerEvent::Burn(e) => Some(&e.route),
+            RelayerEvent::Attestation(e) => Some(&e.route),
+            RelayerEvent::Quarantine(e) => Some(&e.route),
+            RelayerEvent::Submission(e) => e.route.as_deref(),
+            RelayerEvent::Pause(e) => e.route.as_deref(),
+            RelayerEvent::Confirmation(e) => e.route.as_deref(),
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(&Envelope { id: None, v: SCHEMA_VERSION, event: self }).expect("events serialize")
+    }
+
+    /// Rejects versions newer than this build understands.
+    pub fn from_json(s: &str) -> Result<Self> {
+        let env: Envelope<RelayerEvent> =
+            serde_json::from_str(s).map_err(|e| RelayerError::Decode(format!("event: {e}")))?;
+        if env.v > SCHEMA_VERSION {
+            return Err(RelayerError::Decode(format!(
+                "event schema v{} is newer than supported v{SCHEMA_VERSION}",
+                env.v
+            )));
+        }
+        Ok(env.event)
+    }
+}
+
+/// One logged event. `id` increases monotonically and is what SSE clients
+/// send back as `Last-Event-ID`.
+#[derive(Debug, Clone)]
+pub struct EventRecord {
+    pub id: i64,
+    pub event: RelayerEvent,
+}
+
+impl EventRecord {
+    /// The event encoding with the log id in front, `{"id":7,"v":1,...}`,
+    /// for transports that have no id field of their own.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(&Envelope { id: Some(self.id), v: SCHEMA_VERSION, event: &self.event })
+            .expect("events serialize")
+    }
+}
+
+/// Writes every event to the `events` table, then fans it out to live
+/// subscribers. Subscribers that fall behind the channel can catch up from
+/// the table, so nothing is lost to a slow consumer.
+#[derive(Clone)]
+pub struct EventBus {
+    db: SqlitePool,
+    tx: broadcast::Sender<Arc<EventRecord>>,
+    /// Held from insert to send, so events are broadcast in id order.
+    /// Subscribers drop ids at or below the last one seen; an out-of-order
+    /// send would lose the lower id for good.
+    order: Arc<tokio::sync::Mutex<()>>,
+}
+
+impl EventBus {
+    pub fn new(db: SqlitePool, capacity: usize) -> Self {
+        let (tx, _) = broadcast::channel(capacity);
+        Self { db, tx, order: Arc::default() }
+    }
+
+    /// Persist and broadcast. A storage failure is logged rather than
+    /// returned: the state change the event describes is already committed
+    /// and must not be rolled back or retried because of it.
+    pub async fn emit(&self, event: RelayerEvent) -> Option<i64> {
+        let name = event.name();
+        crate::metrics::METRICS.events.with_label_values(&[name]).inc();
+        let data = event.to_json();
+        let _order = self.order.lock().await;
+        match crate::repo::insert_event(&self.db, name, &data).await {
+            Ok(id) => {
+                let _ = self.tx.send(Arc::new(EventRecord { id, event }));
+                Some(id)
+            }
+            Err(e) => {
+                tracing::error!("event {name} not persisted: {e}");
+                None
+            }
+        }
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<Arc<EventRecord>> {
+        self.tx.subscribe()
+    }
+
+    /// Events with `id > after`, oldest first. Rows that no longer decode are
+    /// skipped, so the result can be shorter than `limit` before the head.
+    pub async fn replay(&self, after: i64, limit: i64) -> Result<Vec<EventRecord>> {
+        let rows = crate::repo::list_events_after(&self.db, after, limit).await?;
+        Ok(rows
+            .into_iter()
+            .filter_map(|r| match RelayerEvent::from_json(&r.data) {
+                Ok(event) => Some(EventRecord { id: r.id, event }),
+                Err(e) => {
+                    tracing::warn!("skipping event #{} ({}): {e}", r.id, r.name);
+                    None
+                }
+            })
+            .collect())
+    }
+
+    pub async fn oldest_id(&self) -> Result<Option<i64>> {
+        crate::repo::oldest_event_id(&self.db).await
+    }
+}
+
+/// Worker deleting events older than `retention`, hourly.
+pub async fn prune(db: SqlitePool, retention: Duration, shutdown: CancellationToken) -> Result<()> {
+    while !shutdown.is_cancelled() {
+        let cutoff = crate::utils::now_ts().saturating_sub(retention.as_secs()) as i64;
+        let n = crate::repo::delete_events_before(&db, cutoff).await?;
+        if n > 0 {
+            tracing::info!("pruned {n} events older than {retention:?}");
+        }
+        tokio::select! {
+            _ = tokio::time::sleep(Duration::from_secs(3600)) => {}
+            _ = shutdown.cancelled() => {}
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encoding_is_flat_versioned_and_stable() {
+        let ev = RelayerEvent::Submission(SubmissionEvent {
+            hash_hex: "ab".into(),
+            src_tx: Some("tx".into()),
+            owner: None,
+            route: Some("USDT".into()),
+            dst: None,
+            status: SubmissionStatus::Failed,
+            error: Some("rpc".into()),
+            sol_signature: None,
+        });
+        // Field names and order are part of the contract with consumers.
+        assert_eq!(
+            ev.to_json(),
+            r#"{"v":1,"type":"submission","hash_hex":"ab","src_tx":"tx","owner":null,"route":"USDT","dst":null,"status":"failed","error":"rpc","sol_signature":null}"#
+        );
+        assert_eq!(RelayerEvent::from_json(&ev.to_json()).unwrap(), ev);
+
+        let newer = r#"{"v":2,"type":"confirmation","hash_hex":"ab","sol_signature":"s","slot":1}"#;
+        assert!(RelayerEvent::from_json(newer).is_err());
+        let extra = r#"{"v":1,"type":"confirmation","hash_hex":"ab","sol_signature":"s","slot":1,"new":0}"#;
+        assert_eq!(RelayerEvent::from_json(extra).unwrap().name(), "confirmation");
+    }
+}
@@ -0,0 +1,316 @@
+use serde::Serialize;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use sqlx::SqlitePool;
+use ed25519_dalek::Verifier;
+use std::{
+    future::Future,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use crate::config::Cfg;
+use crate::errors::{RelayerError, Result};
+use crate::pause::PauseState;
+use crate::supervisor::{Supervisor, WorkerState, WorkerStatus};
+use crate::ton::watcher::TonTip;
+
+/// How long toncenter and Solana RPC answers are reused by readiness checks.
+const DEPENDENCY_TTL: Duration = Duration::from_secs(5);
+
+/// Answer to "can this relayer relay right now?". Local checks run on each
+/// request; provider answers come from a [`DependencyCache`] snapshot at most
+/// [`DEPENDENCY_TTL`] old.
+#[derive(Debug, Serialize)]
+pub struct Readiness {
+    pub ready: bool,
+    pub shutting_down: bool,
+    /// Unix time the toncenter and Solana RPC answers were fetched.
+    pub checked_at: u64,
+    /// Operator pauses in effect. Pausing does not make the relayer unready,
+    /// so load balancers keep routing the admin calls that resume it.
+    pub paused: PauseState,
+    pub db: DbCheck,
+    pub ton: TonCheck,
+    pub solana: SolanaCheck,
+    pub signer: SignerCheck,
+    pub workers: WorkersCheck,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DbCheck {
+    pub ok: bool,
+    pub latency_ms: u64,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TonCheck {
+    pub ok: bool,
+    /// Unix time of the last ingest tick that polled every route.
+    pub last_poll_at: Option<u64>,
+    pub tip_seqno: Option<u64>,
+    pub tip_utime: Option<u64>,
+    /// Seconds between the chain tip and the last successful poll.
+    pub lag_secs: Option<u64>,
+    pub max_lag_secs: u64,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SolanaCheck {
+    pub ok: bool,
+    pub slot: Option<u64>,
+    /// Highest slot the RPC node has received shreds for (its view of the tip).
+    pub tip_slot: Option<u64>,
+    pub slot_lag: Option<u64>,
+    pub max_slot_lag: u64,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SignerCheck {
+    pub ok: bool,
+    pub pubkey_hex: Option<String>,
+    /// Whether the key is part of the configured signer set (and so counts
+    /// towards the threshold).
+    pub in_signer_set: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct WorkersCheck {
+    pub ok: bool,
+    pub list: Vec<WorkerStatus>,
+}
+
+/// Liveness: the process is serving and no worker has been given up on.
+/// Dependency outages do not fail liveness, since a restart would not fix them.
+pub fn liveness(sup: &Supervisor) -> (bool, Vec<WorkerStatus>) {
+    let list = sup.statuses();
+    (list.iter().all(|w| w.state != WorkerState::Failed), list)
+}
+
+/// Last provider answers, shared by every readiness request so probes and
+/// anonymous callers cannot turn into provider traffic. Concurrent requests
+/// wait for a single refresh instead of each making their own calls.
+#[derive(Clone, Default)]
+pub struct DependencyCache(Arc<tokio::sync::Mutex<Option<Snapshot>>>);
+
+#[derive(Clone)]
+struct Snapshot {
+    fetched: Instant,
+    at: u64,
+    ton: Result<TonTip, String>,
+    /// Node slot and tip slot.
+    solana: Result<(u64, u64), String>,
+}
+
+impl DependencyCache {
+    async fn get(&self, cfg: &Cfg) -> Snapshot {
+        let mut cached = self.0.lock().await;
+        if let Some(s) = cached.as_ref().filter(|s| s.fetched.elapsed() < DEPENDENCY_TTL) {
+            return s.clone();
+        }
+        let (ton, solana) =
+            tokio::join!(timed(cfg, "toncenter", crate::ton::watcher::masterchain_tip(cfg)), solana_slots(cfg));
+        let s = Snapshot {
+            fetched: Instant::now(),
+            at: crate::utils::now_ts(),
+            ton: ton.map_err(|e| e.to_string()),
+            solana: solana.map_err(|e| e.to_string()),
+        };
+        *cached = Some(s.clone());
+        s
+    }
+}
+
+pub async fn readiness(
+    cfg: &Cfg,
+    db: &SqlitePool,
+    sup: &Supervisor,
+    deps: &DependencyCache,
+    shutting_down: bool,
+) -> Readiness {
+    // A failing database shows up in `db`; no pauses are reported then.
+    let paused = PauseState::load(db).await.unwrap_or_default();
+    let ingest_paused = paused.global.is_some();
+    let (db_check, snapshot) = tokio::join!(check_db(cfg, db), deps.get(cfg));
+    let ton = check_ton(cfg, db, ingest_paused, snapshot.ton).await;
+    let solana = check_solana(cfg, snapshot.solana);
+    let signer = check_signer(&cfg.relayer_sk_base64, &cfg.signers);
+    let list = sup.statuses();
+    let workers = WorkersCheck { ok: list.iter().all(|w| w.state == WorkerState::Running), list };
+
+    let ready = !shutting_down && db_check.ok && ton.ok && solana.ok && signer.ok && workers.ok;
+    Readiness { ready, shutting_down, checked_at: snapshot.at, paused, db: db_check, ton, solana, signer, workers }
+}
+
+async fn timed<T>(cfg: &Cfg, what: &str, fut: impl Future<Output = Result<T>>) -> Result<T> {
+    tokio::time::timeout(cfg.health_check_timeout, fut)
+        .await
+        .map_err(|_| RelayerError::Network(format!("{what} timed out after {:?}", cfg.health_check_timeout)))?
+}
+
+async fn check_db(cfg: &Cfg, db: &SqlitePool) -> DbCheck {
+    let started = Instant::now();
+    let res = timed(cfg, "database", async {
+        sqlx::query("SELECT 1").execute(db).await?;
+        Ok(())
+    })
+    .await;
+    DbCheck {
+        ok: res.is_ok(),
+        latency_ms: started.elapsed().as_millis() as u64,
+        error: res.err().map(|e| e.to_string()),
+    }
+}
+
+/// While ingest is paused nothing polls, so poll lag is reported but not
+/// held against readiness.
+async fn check_ton(cfg: &Cfg, db: &SqlitePool, ingest_paused: bool, tip: Result<TonTip, String>) -> TonCheck {
+    let max_lag_secs = cfg.health_ton_max_lag.as_secs();
+    let last_poll_at = crate::repo::get_cursor(db, crate::ingest::ton::LAST_POLL_KEY)
+        .await
+        .ok()
+        .flatten()
+        .and_then(|v| v.parse::<u64>().ok());
+
+    let mut check = TonCheck {
+        ok: false,
+        last_poll_at,
+        tip_seqno: None,
+        tip_utime: None,
+        lag_secs: None,
+        max_lag_secs,
+        error: None,
+    };
+    match tip {
+        Ok(tip) => {
+            check.tip_seqno = Some(tip.seqno);
+            check.tip_utime = Some(tip.utime);
+            match last_poll_at {
+                Some(at) => {
+                    let lag = tip.utime.saturating_sub(at);
+                    check.lag_secs = Some(lag);
+                    check.ok = lag <= max_lag_secs || ingest_paused;
+                    if !check.ok {
+                        check.error = Some(format!("last successful poll is {lag}s behind the tip"));
+                    }
+                }
+                None if ingest_paused => check.ok = true,
+                None => check.error = Some("no successful poll yet".into()),
+            }
+        }
+        Err(e) => check.error = Some(e),
+    }
+    check
+}
+
+/// The RPC node's slot and its view of the tip.
+async fn solana_slots(cfg: &Cfg) -> Result<(u64, u64)> {
+    let rpc = RpcClient::new_with_timeout(cfg.sol_rpc_http.to_string(), cfg.health_check_timeout);
+    timed(cfg, "solana rpc", async {
+        tokio::try_join!(
+            rpc_call("getSlot", rpc.get_slot()),
+            rpc_call("getMaxShredInsertSlot", rpc.get_max_shred_insert_slot()),
+        )
+    })
+    .await
+}
+
+fn check_solana(cfg: &Cfg, slots: Result<(u64, u64), String>) -> SolanaCheck {
+    let max_slot_lag = cfg.health_sol_max_slot_lag;
+    match slots {
+        Ok((slot, tip)) => {
+            let lag = tip.saturating_sub(slot);
+            SolanaCheck {
+                ok: lag <= max_slot_lag,
+                slot: Some(slot),
+                tip_slot: Some(tip),
+                slot_lag: Some(lag),
+                max_slot_lag,
+                error: (lag > max_slot_lag).then(|| format!("RPC node is {lag} slots behind")),
+            }
+        }
+        Err(e) => SolanaCheck {
+            ok: false,
+            slot: None,
+            tip_slot: None,
+            slot_lag: None,
+            max_slot_lag,
+            error: Some(e),
+        },
+    }
+}
+
+/// Await a Solana RPC call, recording its latency and outcome in the metrics.
+pub(crate) async fn rpc_call<T>(
+    method: &str,
+    fut: impl Future<Output = solana_client::client_error::Result<T>>,
+) -> Result<T> {
+    let started = Instant::now();
+    let res = fut.await;
+    let status = if res.is_ok() { "ok" } else { "error" };
+    crate::metrics::METRICS.provider_request("solana_rpc", method, status, started.elapsed());
+    res.map_err(|e| RelayerError::Network(format!("solana rpc {method}: {e}")))
+}
+
+/// Load the relayer key and prove it can sign; a key outside the signer set
+/// produces attestations the program would not count, so it is not ready.
+fn check_signer(sk_b64: &str, signers: &[[u8; 32]]) -> SignerCheck {
+    let kp = match crate::signer::load_keypair_base64(sk_b64) {
+        Ok(kp) => kp,
+        Err(e) => {
+            return SignerCheck { ok: false, pubkey_hex: None, in_signer_set: false, error: Some(format!("{e:#}")) }
+        }
+    };
+    let pubkey = kp.public.to_bytes();
+    let sig = ed25519_dalek::Signature::from(crate::signer::sign(&[0u8; 32], &kp));
+    let signs = kp.public.verify(&[0u8; 32], &sig).is_ok();
+    let in_signer_set = signers.contains(&pubkey);
+
+    let error = if !signs {
+        Some("test signature does not verify".into())
+    } else if !in_signer_set {
+        Some("relayer key is not in bridge.signers".into())
+    } else {
+        None
+    };
+    SignerCheck { ok: error.is_none(), pubkey_hex: Some(hex::encode(pubkey)), in_signer_set, error }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use base64::Engine;
+
+    #[tokio::test]
+    async fn provider_answers_are_reused_within_the_ttl() {
+        let mut cfg = crate::config::tests::cfg_with("");
+        cfg.ton_api_base = "http://127.0.0.1:9".parse().unwrap();
+        cfg.sol_rpc_http = "http://127.0.0.1:9".parse().unwrap();
+        let deps = DependencyCache::default();
+        let first = deps.get(&cfg).await;
+        assert!(first.ton.is_err() && first.solana.is_err());
+        assert_eq!(deps.get(&cfg).await.fetched, first.fetched);
+
+        deps.0.lock().await.as_mut().unwrap().fetched -= DEPENDENCY_TTL;
+        assert!(deps.get(&cfg).await.fetched > first.fetched);
+    }
+
+    #[test]
+    fn signer_must_be_in_signer_set() {
+        let secret = ed25519_dalek::SecretKey::from_bytes(&[3u8; 32]).unwrap();
+        let kp = ed25519_dalek::Keypair { public: (&secret).into(), secret };
+        let sk = base64::engine::general_purpose::STANDARD.encode(kp.secret.as_bytes());
+
+        let c = check_signer(&sk, &[kp.public.to_bytes()]);
+        assert!(c.ok && c.in_signer_set, "{c:?}");
+
+        let c = check_signer(&sk, &[[7u8; 32]]);
+        assert!(!c.ok && !c.in_signer_set);
+
+        let c = check_signer("not base64!", &[kp.public.to_bytes()]);
+        assert!(!c.ok && c.pubkey_hex.is_none());
+    }
+}
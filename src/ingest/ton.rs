@@ -1,112 +1,628 @@
-use anyhow::{anyhow, Result};
-use borsh::BorshSerialize;
-use hex::FromHex;
 use sqlx::SqlitePool;
-use tracing::info;
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
 use base64::Engine; // <-- add
-use bs58;           // make sure bs58 is in Cargo.toml
 
-use crate::attestations::{TonToSolAttestationV1, domain_hash};
-use crate::repo::{insert_attestation, insert_burn, set_cursor};
-use crate::ton::watcher::{poll_latest_burn, TonBurn};
+use crate::amounts::Normalized;
+use crate::attestations::{TonToSolAttestationV2, domain_hash, CHAIN_TON, TON_TO_SOL_DOMAIN_V2};
+use crate::config::Cfg;
+use crate::errors::{RelayerError, Result};
+use crate::events::{AttestationEvent, BurnEvent, EventBus, QuarantineEvent, RelayerEvent};
+use crate::metrics::METRICS;
+use crate::pause::PauseState;
+use crate::repo::{self, set_cursor};
+use crate::routes::Route;
+use crate::ton::address::TonAddress;
+use crate::ton::watcher::{poll_burns, RawBurn, TonBurn, BURN_PAGE};
 
-fn cfg_hash_from_env() -> Result<[u8; 32]> {
-    let s = std::env::var("CFG_HASH_HEX").unwrap_or_default();
-    let bytes = <[u8; 32]>::from_hex(s).map_err(|e| anyhow!("CFG_HASH_HEX bad hex: {e}"))?;
-    Ok(bytes)
-}
-
-fn ton_minter_wc_hash36_demo() -> [u8; 36] { [0u8; 36] }
-
-fn dst_solana_pubkey_from_env() -> Result<[u8; 32]> {
-    let s = std::env::var("DST_SOL_PUBKEY_BASE58")
-        .map_err(|_| anyhow!("DST_SOL_PUBKEY_BASE58 missing"))?;
-    let v = bs58::decode(s).into_vec().map_err(|e| anyhow!("dst sol pubkey bad base58: {e}"))?;
-    if v.len() != 32 { return Err(anyhow!("dst sol pubkey must be 32 bytes")); }
-    let mut k = [0u8; 32];
-    k.copy_from_slice(&v);
-    Ok(k)
-}
-
-fn b64_to_32(b64: &str) -> Result<[u8; 32]> {
+pub fn b64_to_32(b64: &str) -> Result<[u8; 32]> {
     let v = base64::engine::general_purpose::STANDARD
         .decode(b64)
-        .map_err(|e| anyhow!("b64 decode: {e}"))?;
-    if v.len() != 32 { return Err(anyhow!("expected 32 bytes after b64 decode")); }
+        .map_err(|e| RelayerError::Decode(format!("b64 decode: {e}")))?;
+    if v.len() != 32 { return Err(RelayerError::Decode("expected 32 bytes after b64 decode".into())); }
     let mut a = [0u8; 32];
     a.copy_from_slice(&v);
     Ok(a)
 }
 
-pub async fn run(db: SqlitePool, tx: tokio::sync::broadcast::Sender<String>) -> Result<()> {
-    let cfg_hash = cfg_hash_from_env()?;
-    let minter36 = ton_minter_wc_hash36_demo();
-    let dst_sol = dst_solana_pubkey_from_env()?;
-    let decimals: u8 = std::env::var("JETTON_DECIMALS").ok().and_then(|s| s.parse().ok()).unwrap_or(9);
+/// Why a burn was held back from signing.
+#[derive(Debug)]
+pub struct Rejection {
+    pub code: &'static str,
+    pub detail: String,
+    /// Policy rejections (limits, precision, payload) can be overridden by an
+    /// operator; malformed data cannot.
+    pub releasable: bool,
+}
 
-    loop {
-        if let Some(burn) = poll_latest_burn().await? {
-            let inserted = insert_burn(
-                &db,
-                &burn.tx_hash_b64,
-                burn.lt as i64,
-                burn.utime as i64,
-                &burn.jetton_master_raw,
-                &burn.owner_raw,
-                &burn.jetton_wallet_raw,
-                &burn.amount_raw,
-                burn.custom_payload.as_deref(),
-            ).await?;
-
-            if inserted > 0 {
-                let amount_raw_u128 = burn.amount_raw.parse::<u128>().unwrap_or(0);
-                let tx_hash_32 = b64_to_32(&burn.tx_hash_b64)?;
-
-                let att = TonToSolAttestationV1 {
-                    ver: 1,
-                    src_chain: 0,
-                    kind: 1,
-                    cfg_hash,
-                    nonce: 0,
-                    jetton_minter_ton: minter36,
-                    amount_raw: amount_raw_u128,
-                    decimals_ton: decimals,
-                    dst_solana_pubkey: dst_sol,
-                    min_sol_out: None,
-                    deadline_ts: None,
-                    tx_hash_ton: tx_hash_32,
-                    lt_ton: burn.lt,
-                    timestamp_ton: burn.utime,
-                };
+impl Rejection {
+    fn malformed(code: &'static str, detail: impl Into<String>) -> Self {
+        Self { code, detail: detail.into(), releasable: false }
+    }
 
-                let bytes = att.try_to_vec()?; // works once BorshSerialize is derived
-                let h = domain_hash("TON→SOL_BRIDGE_V1", &bytes);
-                let hhex = hex::encode(h);
+    fn policy(code: &'static str, detail: impl Into<String>) -> Self {
+        Self { code, detail: detail.into(), releasable: true }
+    }
+}
 
-                let _att_id = insert_attestation(&db, "TON_TO_SOL", &bytes, &hhex, Some(&burn.tx_hash_b64)).await?;
+struct Checked {
+    burn: TonBurn,
+    tx_hash: [u8; 32],
+    amount_raw: u128,
+    normalized: Normalized,
+}
 
-                if let Err(e) = crate::solana::submit::submit_ton_attestation(&crate::config::Cfg::from_env()?, &att).await {
-                    tracing::warn!("submit attestation failed: {e}");
-                }
+/// Validate a provider burn against its route. With `release` set, policy
+/// rejections are waived (operator override) and dust is truncated.
+fn check_burn(route: &Route, raw: &RawBurn, release: bool) -> Result<Checked, Rejection> {
+    let tx_hash_b64 = raw
+        .tx_hash_b64()
+        .ok_or_else(|| Rejection::malformed("bad_tx_hash", "transaction_hash missing"))?;
+    let tx_hash = b64_to_32(tx_hash_b64).map_err(|e| Rejection::malformed("bad_tx_hash", format!("{e}")))?;
 
-                let _ = tx.send(
-                    serde_json::json!({
-                        "type":"attestation",
-                        "kind":"TON_TO_SOL",
-                        "hash_hex": hhex,
-                        "src_tx": burn.tx_hash_b64,
-                        "lt": burn.lt,
-                        "utime": burn.utime,
-                        "amount_raw": burn.amount_raw
-                    }).to_string()
-                );
+    let lt = match raw.u64("transaction_lt") {
+        Some(Ok(lt)) if lt > 0 => lt,
+        Some(Ok(_)) => return Err(Rejection::malformed("bad_lt", "transaction_lt is 0")),
+        Some(Err(e)) => return Err(Rejection::malformed("bad_lt", e)),
+        None => return Err(Rejection::malformed("bad_lt", "transaction_lt missing")),
+    };
+    let utime = match raw.u64("transaction_now") {
+        Some(Ok(t)) => t,
+        Some(Err(e)) => return Err(Rejection::malformed("bad_utime", e)),
+        None => return Err(Rejection::malformed("bad_utime", "transaction_now missing")),
+    };
 
-                set_cursor(&db, "ton_last_lt", &burn.lt.to_string()).await.ok();
+    let master_raw = raw.str("jetton_master").unwrap_or_default();
+    if master_raw.parse::<TonAddress>().ok() != Some(route.ton_master) {
+        return Err(Rejection::malformed(
+            "unknown_master",
+            format!("burn for master {master_raw:?} returned on route {}", route.name),
+        ));
+    }
+    // Stored in raw form whatever the provider sent, so they compare as strings.
+    let address = |key: &str| -> Result<TonAddress, Rejection> {
+        let s = raw.str(key).ok_or_else(|| Rejection::malformed("missing_field", format!("{key} missing")))?;
+        s.parse().map_err(|e| Rejection::malformed("bad_address", format!("{key} {s:?}: {e}")))
+    };
+    let owner = address("owner")?;
+    let wallet = address("jetton_wallet")?;
 
-                info!("ingested burn→attestation lt={} hash={}", burn.lt, hhex);
+    let amount_str = raw.str("amount").unwrap_or_default();
+    let amount_raw: u128 = amount_str
+        .parse()
+        .map_err(|e| Rejection::malformed("bad_amount", format!("amount {amount_str:?}: {e}")))?;
+    if amount_raw == 0 {
+        return Err(Rejection::malformed("zero_amount", "amount is 0"));
+    }
+
+    let custom_payload = match raw.str("custom_payload") {
+        Some(p) => Some(
+            base64::engine::general_purpose::STANDARD
+                .decode(p)
+                .map_err(|e| Rejection::malformed("bad_payload", format!("custom_payload: {e}")))?,
+        ),
+        None if route.require_payload && !release => {
+            return Err(Rejection::policy("missing_payload", "custom_payload missing"));
+        }
+        None => None,
+    };
+
+    if !release {
+        route.check_amount(amount_raw).map_err(|e| Rejection::policy("route_limit", e))?;
+    }
+    let normalized = match route.normalize(amount_raw) {
+        Ok(n) => n,
+        Err(e) => {
+            let forced = release
+                .then(|| crate::amounts::ton_to_sol(amount_raw, route.decimals_ton, route.decimals_sol).ok())
+                .flatten()
+                .filter(|n| n.amount_sol > 0);
+            forced.ok_or_else(|| Rejection::policy("precision", e))?
+        }
+    };
+
+    Ok(Checked {
+        burn: TonBurn {
+            tx_hash_b64: tx_hash_b64.to_string(),
+            lt,
+            utime,
+            jetton_master_raw: route.ton_master.to_raw(),
+            owner_raw: owner.to_raw(),
+            jetton_wallet_raw: wallet.to_raw(),
+            // Canonical form, so stored amounts compare correctly as strings.
+            amount_raw: amount_raw.to_string(),
+            custom_payload,
+        },
+        tx_hash,
+        amount_raw,
+        normalized,
+    })
+}
+
+/// Cursor holding the unix time of the last completed tick.
+pub const LAST_POLL_KEY: &str = "ton_last_poll_at";
+
+/// Poll loop. A failing route is logged and skipped until the next tick.
+/// Retryable errors outside the routes (network, rate limits, busy DB) are
+/// retried on the next tick; anything else stops the worker.
+///
+/// Shutdown is only observed between ticks, so a burn that is being attested
+/// always gets its DB writes and cursor update completed.
+pub async fn run(
+    cfg: Cfg,
+    db: SqlitePool,
+    bus: EventBus,
+    shutdown: CancellationToken,
+) -> Result<()> {
+    while !shutdown.is_cancelled() {
+        let mut delay = cfg.ton_poll_interval;
+        if let Err(e) = tick(&cfg, &db, &bus).await {
+            if !e.is_retryable() {
+                return Err(e);
             }
+            warn!("ingest tick failed ({}), retrying: {e}", e.kind());
+            delay = delay.max(e.retry_after().unwrap_or_default());
+        }
+        tokio::select! {
+            _ = tokio::time::sleep(delay) => {}
+            _ = shutdown.cancelled() => {}
+        }
+    }
+    info!("TON ingest stopped");
+    Ok(())
+}
+
+/// One pass over every route. Paused routes are not polled and nothing is
+/// signed for them; their cursor stays put, and since polling pages forward
+/// from it, burns made during the pause are picked up after a resume.
+async fn tick(cfg: &Cfg, db: &SqlitePool, bus: &EventBus) -> Result<()> {
+    let pauses = PauseState::load(db).await?;
+    if pauses.global.is_some() {
+        tracing::debug!("ingest paused");
+        return Ok(());
+    }
+    process_released(cfg, db, bus, &pauses).await?;
+    for route in cfg.routes.enabled() {
+        if pauses.route_paused(&route.name) {
+            tracing::debug!("route {} paused, not polling", route.name);
+            continue;
+        }
+        match ingest_route(cfg, db, bus, route).await {
+            Ok(()) => {}
+            // Every route shares the provider, so back off for all of them.
+            Err(e @ RelayerError::RateLimited { .. }) => return Err(e),
+            // One bad route must not hold back the others or restart the worker.
+            Err(e) => warn!("route {}: ingest failed ({}): {e}", route.name, e.kind()),
+        }
+    }
+    set_cursor(db, LAST_POLL_KEY, &crate::utils::now_ts().to_string()).await
+}
+
+/// Process the route's burns past its cursor in lt order, moving the cursor
+/// after each one. The first failure stops the route, so the burn is retried
+/// on the next tick rather than skipped.
+#[tracing::instrument(skip_all, fields(route = %route.name))]
+async fn ingest_route(
+    cfg: &Cfg,
+    db: &SqlitePool,
+    bus: &EventBus,
+    route: &Route,
+) -> Result<()> {
+    let mut cursor = repo::get_cursor(db, &route.cursor_key()).await?.and_then(|c| c.parse::<u64>().ok());
+    loop {
+        let burns = poll_burns(cfg, &route.ton_master, cursor).await?;
+        let start = cursor;
+        for raw in &burns {
+            let res = match check_burn(route, raw, false) {
+                Ok(checked) => attest_burn(cfg, db, bus, route, checked).await,
+                Err(rej) => quarantine(db, bus, route, raw, rej).await,
+            };
+            let lt = raw.u64("transaction_lt").and_then(|lt| lt.ok());
+            if let Err(e) = res {
+                // Measured from the cursor to the burn we failed to process.
+                let lag_lt = lt.map_or(0, |lt| lt.saturating_sub(cursor.unwrap_or(0)));
+                let utime = raw.u64("transaction_now").and_then(|t| t.ok());
+                let lag_secs = utime.map_or(0, |t| crate::utils::now_ts().saturating_sub(t));
+                METRICS.cursor_lag_lt.with_label_values(&[&route.name]).set(lag_lt as i64);
+                METRICS.cursor_lag_seconds.with_label_values(&[&route.name]).set(lag_secs as i64);
+                return Err(e);
+            }
+            // A burn whose lt does not parse was quarantined; the next one moves past it.
+            if let Some(lt) = lt.filter(|lt| Some(*lt) > cursor) {
+                set_cursor(db, &route.cursor_key(), &lt.to_string()).await?;
+                cursor = Some(lt);
+            }
+        }
+        // A full page means more may be waiting; stop if it did not move us.
+        if burns.len() < BURN_PAGE || cursor == start {
+            break;
+        }
+    }
+    METRICS.cursor_lag_lt.with_label_values(&[&route.name]).set(0);
+    METRICS.cursor_lag_seconds.with_label_values(&[&route.name]).set(0);
+    Ok(())
+}
+
+#[tracing::instrument(skip_all, fields(route = %route.name, ton_tx = raw.tx_hash_b64().unwrap_or("?"), code = rej.code))]
+async fn quarantine(
+    db: &SqlitePool,
+    bus: &EventBus,
+    route: &Route,
+    raw: &RawBurn,
+    rej: Rejection,
+) -> Result<()> {
+    let raw_json = raw.0.to_string();
+    // A burn can be read again (a page re-read after a failure, or a new
+    // route's latest burn), so key entries by tx hash (or by content when even
+    // that is unusable) to store each one once.
+    let qkey = match raw.tx_hash_b64() {
+        Some(h) => h.to_string(),
+        None => hex::encode(domain_hash("QUARANTINE", raw_json.as_bytes())),
+    };
+    let inserted = repo::insert_quarantine(
+        db,
+        &repo::NewQuarantine {
+            qkey: &qkey,
+            tx_hash_b64: raw.tx_hash_b64(),
+            jetton_master_raw: &route.ton_master.to_raw(),
+            reason_code: rej.code,
+            reason: &rej.detail,
+            releasable: rej.releasable,
+            raw_json: &raw_json,
+        },
+    )
+    .await?;
+    if inserted == 0 {
+        return Ok(());
+    }
+
+    warn!("route {}: burn {} quarantined: {} ({})", route.name, qkey, rej.code, rej.detail);
+    METRICS.burns_quarantined.with_label_values(&[&route.name, rej.code]).inc();
+    bus.emit(RelayerEvent::Quarantine(QuarantineEvent {
+        route: route.name.clone(),
+        key: qkey,
+        code: rej.code.into(),
+        reason: rej.detail,
+        releasable: rej.releasable,
+    }))
+    .await;
+    Ok(())
+}
+
+/// Attest burns an operator released from quarantine through the admin API.
+async fn process_released(cfg: &Cfg, db: &SqlitePool, bus: &EventBus, pauses: &PauseState) -> Result<()> {
+    for q in repo::list_quarantine(db, Some("released"), 100).await? {
+        let raw = match serde_json::from_str(&q.raw_json) {
+            Ok(v) => RawBurn(v),
+            Err(e) => {
+                repo::set_quarantine_status(db, q.id, "held", Some(&format!("release failed: {e}"))).await?;
+                continue;
+            }
+        };
+        let route = q.jetton_master_raw.parse::<TonAddress>().ok().and_then(|m| cfg.routes.get(&m));
+        let Some(route) = route else {
+            repo::set_quarantine_status(db, q.id, "held", Some("release failed: route no longer configured")).await?;
+            continue;
+        };
+        if pauses.route_paused(&route.name) {
+            continue;
+        }
+        match check_burn(route, &raw, true) {
+            Ok(checked) => {
+                info!("quarantine #{}: released by operator, attesting {}", q.id, checked.burn.tx_hash_b64);
+                attest_burn(cfg, db, bus, route, checked).await?;
+                repo::set_quarantine_status(db, q.id, "processed", None).await?;
+            }
+            Err(rej) => {
+                let reason = format!("release failed: {}: {}", rej.code, rej.detail);
+                repo::set_quarantine_status(db, q.id, "held", Some(&reason)).await?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The attestation for `burn` under the current config: route decimals,
+/// `cfg_hash` and destination are read from `cfg`, not from any earlier
+/// attestation.
+pub fn build_attestation(
+    cfg: &Cfg,
+    route: &Route,
+    burn: &TonBurn,
+    tx_hash: [u8; 32],
+    amount_raw: u128,
+    normalized: &Normalized,
+) -> TonToSolAttestationV2 {
+    TonToSolAttestationV2 {
+        ver: 2,
+        src_chain: CHAIN_TON,
+        kind: 1,
+        cfg_hash: cfg.cfg_hash,
+        nonce: 0,
+        jetton_minter_ton: route.ton_master.wc_hash36(),
+        amount_raw,
+        decimals_ton: route.decimals_ton,
+        amount_sol: normalized.amount_sol,
+        decimals_sol: route.decimals_sol,
+        dst_solana_pubkey: cfg.dst_sol_pubkey.to_bytes(),
+        min_sol_out: None,
+        deadline_ts: None,
+        tx_hash_ton: tx_hash,
+        lt_ton: burn.lt,
+        timestamp_ton: burn.utime,
+    }
+}
+
+/// Borsh bytes of an attestation with their hash and the relayer's signature.
+pub struct Signed {
+    pub bytes: Vec<u8>,
+    pub hash_hex: String,
+    signer_hex: String,
+    signature_hex: String,
+}
+
+pub fn sign_attestation(cfg: &Cfg, att: &TonToSolAttestationV2) -> Result<Signed> {
+    let bytes = borsh::to_vec(att).map_err(|e| RelayerError::Invariant(format!("borsh serialize: {e}")))?;
+    let h = domain_hash(TON_TO_SOL_DOMAIN_V2, &bytes);
+    let kp = crate::signer::load_keypair_base64(&cfg.relayer_sk_base64)
+        .map_err(|e| RelayerError::Signing(format!("{e:#}")))?;
+    let sig = crate::signer::sign(&h, &kp);
+    Ok(Signed {
+        bytes,
+        hash_hex: hex::encode(h),
+        signer_hex: hex::encode(kp.public.as_bytes()),
+        signature_hex: hex::encode(sig),
+    })
+}
+
+impl Signed {
+    /// The attestation row for `burn`; `amount_dst` and `dust_raw` are the
+    /// normalized amounts as decimal strings.
+    fn row<'a>(&'a self, burn: &'a TonBurn, amount_dst: &'a str, dust_raw: &'a str) -> repo::NewAttestation<'a> {
+        repo::NewAttestation {
+            kind: "TON_TO_SOL",
+            payload_borsh: &self.bytes,
+            payload_hash_hex: &self.hash_hex,
+            src_tx_hash_b64: Some(&burn.tx_hash_b64),
+            amount_dst: Some(amount_dst),
+            dust_raw: Some(dust_raw),
+            signer_hex: &self.signer_hex,
+            signature_hex: &self.signature_hex,
+        }
+    }
+}
+
+fn burn_row(burn: &TonBurn) -> repo::NewBurn<'_> {
+    repo::NewBurn {
+        tx_hash_b64: &burn.tx_hash_b64,
+        lt: burn.lt as i64,
+        utime: burn.utime as i64,
+        jetton_master_raw: &burn.jetton_master_raw,
+        owner_raw: &burn.owner_raw,
+        jetton_wallet_raw: &burn.jetton_wallet_raw,
+        amount_raw: &burn.amount_raw,
+        custom_payload: burn.custom_payload.as_deref(),
+    }
+}
+
+/// Queue a signed attestation for an already stored burn and announce it.
+pub async fn store_attestation(
+    cfg: &Cfg,
+    db: &SqlitePool,
+    bus: &EventBus,
+    route: &Route,
+    burn: &TonBurn,
+    signed: &Signed,
+    normalized: &Normalized,
+) -> Result<()> {
+    let (amount_dst, dust_raw) = (normalized.amount_sol.to_string(), normalized.dust_raw.to_string());
+    repo::insert_attestation(db, &signed.row(burn, &amount_dst, &dust_raw)).await?;
+    announce_attestation(cfg, bus, route, burn, signed, normalized).await;
+    Ok(())
+}
+
+async fn announce_attestation(
+    cfg: &Cfg,
+    bus: &EventBus,
+    route: &Route,
+    burn: &TonBurn,
+    signed: &Signed,
+    normalized: &Normalized,
+) {
+    METRICS.attestations.with_label_values(&["created"]).inc();
+
+    bus.emit(RelayerEvent::Attestation(AttestationEvent {
+        kind: "TON_TO_SOL".into(),
+        route: route.name.clone(),
+        hash_hex: signed.hash_hex.clone(),
+        src_tx: burn.tx_hash_b64.clone(),
+        lt: burn.lt,
+        utime: burn.utime,
+        owner: burn.owner_raw.clone(),
+        dst: cfg.dst_sol_pubkey.to_string(),
+        amount_raw: burn.amount_raw.clone(),
+        amount_dst: normalized.amount_sol.to_string(),
+        dust_raw: normalized.dust_raw.to_string(),
+    }))
+    .await;
+}
+
+/// The span carries the TON tx hash from the start and the attestation hash
+/// once it is known, so both identify the burn in logs and traces.
+#[tracing::instrument(
+    skip_all,
+    fields(route = %route.name, ton_tx = %checked.burn.tx_hash_b64, att_hash = tracing::field::Empty)
+)]
+async fn attest_burn(
+    cfg: &Cfg,
+    db: &SqlitePool,
+    bus: &EventBus,
+    route: &Route,
+    checked: Checked,
+) -> Result<()> {
+    let Checked { burn, tx_hash, amount_raw, normalized } = checked;
+
+    // Sign before touching the DB so a key problem never leaves a burn
+    // stored without its attestation.
+    let att = build_attestation(cfg, route, &burn, tx_hash, amount_raw, &normalized);
+    let signed = sign_attestation(cfg, &att)?;
+    tracing::Span::current().record("att_hash", signed.hash_hex.as_str());
+
+    // Burn and attestation are written in one transaction, so a crash in
+    // between cannot leave a stored burn that is never attested.
+    let (amount_dst, dust_raw) = (normalized.amount_sol.to_string(), normalized.dust_raw.to_string());
+    let stored = repo::insert_attested_burn(db, &burn_row(&burn), &signed.row(&burn, &amount_dst, &dust_raw)).await?;
+    if stored.is_none() {
+        return Ok(());
+    }
+    METRICS.burns_ingested.with_label_values(&[&route.name]).inc();
+    bus.emit(RelayerEvent::Burn(BurnEvent {
+        route: route.name.clone(),
+        src_tx: burn.tx_hash_b64.clone(),
+        lt: burn.lt,
+        utime: burn.utime,
+        owner: burn.owner_raw.clone(),
+        amount_raw: burn.amount_raw.clone(),
+    }))
+    .await;
+
+    if normalized.dust_raw > 0 {
+        warn!(
+            "route {}: burn {} truncated, dust {} raw units",
+            route.name, burn.tx_hash_b64, normalized.dust_raw
+        );
+    }
+
+    announce_attestation(cfg, bus, route, &burn, &signed, &normalized).await;
+
+    info!("ingested burn→attestation route={} lt={} hash={}", route.name, burn.lt, signed.hash_hex);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+
+    fn route() -> Route {
+        Route {
+            name: "T".into(),
+            ton_master: "0:83DFD552E63729B472FCBCC8C45EBCC6691702558B68EC7527E1BA403A0F31A8".parse().unwrap(),
+            sol_mint: solana_sdk::pubkey::Pubkey::new_unique(),
+            decimals_ton: 9,
+            decimals_sol: 6,
+            min_amount_raw: 1_000,
+            max_amount_raw: Some(1_000_000_000_000),
+            allow_dust: false,
+            require_payload: false,
+            enabled: true,
+        }
+    }
+
+    fn raw(amount: &str, lt: &str) -> RawBurn {
+        RawBurn(serde_json::json!({
+            "transaction_hash": base64::engine::general_purpose::STANDARD.encode([7u8; 32]),
+            "transaction_lt": lt,
+            "transaction_now": 1_700_000_000u64,
+            "jetton_master": "0:83DFD552E63729B472FCBCC8C45EBCC6691702558B68EC7527E1BA403A0F31A8",
+            "owner": "EQCD39VS5jcptHL8vMjEXrzGaRcCVYto7HUn4bpAOg8xqB2N",
+            "jetton_wallet": format!("0:{}", "bb".repeat(32)),
+            "amount": amount,
+        }))
+    }
+
+    fn code(r: Result<Checked, Rejection>) -> &'static str {
+        r.err().map(|r| r.code).unwrap_or("ok")
+    }
+
+    #[test]
+    fn malformed_burns_are_rejected_not_zeroed() {
+        let r = route();
+        assert_eq!(code(check_burn(&r, &raw("1500000000", "42"), false)), "ok");
+        assert_eq!(code(check_burn(&r, &raw("abc", "42"), false)), "bad_amount");
+        assert_eq!(code(check_burn(&r, &raw("0", "42"), false)), "zero_amount");
+        assert_eq!(code(check_burn(&r, &raw("1500000000", "x"), false)), "bad_lt");
+        assert_eq!(code(check_burn(&r, &RawBurn(serde_json::json!({})), false)), "bad_tx_hash");
+
+        let mut bad_owner = raw("1500000000", "42");
+        bad_owner.0["owner"] = "0:AA".into();
+        assert_eq!(code(check_burn(&r, &bad_owner, false)), "bad_address");
+    }
+
+    #[test]
+    fn addresses_are_stored_in_raw_form() {
+        let r = route();
+        let burn = check_burn(&r, &raw("1500000000", "42"), false).ok().unwrap().burn;
+        assert_eq!(burn.owner_raw, r.ton_master.to_raw());
+        assert_eq!(burn.jetton_wallet_raw, format!("0:{}", "BB".repeat(32)));
+        assert_eq!(burn.jetton_master_raw, r.ton_master.to_raw());
+    }
+
+    #[test]
+    fn policy_rejections_can_be_released() {
+        let r = route();
+        let over = raw("2000000000000", "42");
+        let rej = check_burn(&r, &over, false).err().unwrap();
+        assert_eq!((rej.code, rej.releasable), ("route_limit", true));
+        assert!(check_burn(&r, &over, true).is_ok());
+
+        let dusty = raw("1500000123", "42");
+        assert_eq!(code(check_burn(&r, &dusty, false)), "precision");
+        assert_eq!(check_burn(&r, &dusty, true).ok().unwrap().normalized.dust_raw, 123);
+
+        assert!(!check_burn(&r, &raw("0", "42"), true).err().unwrap().releasable);
+    }
+
+    /// Provider stub serving `burns` the way `poll_burns` pages through them.
+    async fn provider(burns: Arc<Mutex<Vec<serde_json::Value>>>) -> String {
+        use axum::extract::Query;
+        let app = axum::Router::new().route(
+            "/jetton/burns",
+            axum::routing::get(move |Query(q): Query<HashMap<String, String>>| async move {
+                let lt = |b: &serde_json::Value| b["transaction_lt"].as_str().unwrap().parse::<u64>().unwrap();
+                let mut all = burns.lock().unwrap().clone();
+                all.sort_by_key(lt);
+                let page: Vec<_> = match q.get("start_lt") {
+                    Some(start) => all.into_iter().filter(|b| lt(b) >= start.parse().unwrap()).collect(),
+                    None => all.pop().into_iter().collect(),
+                };
+                axum::Json(serde_json::json!({ "jetton_burns": page }))
+            }),
+        );
+        format!("http://{}", crate::api::tests::listen(app).await)
+    }
+
+    fn burn_at(lt: u8) -> serde_json::Value {
+        let mut b = raw("1500000000", &lt.to_string()).0;
+        b["transaction_hash"] = base64::engine::general_purpose::STANDARD.encode([lt; 32]).into();
+        b
+    }
+
+    #[tokio::test]
+    async fn nothing_is_signed_while_paused_and_nothing_is_missed() {
+        let burns = Arc::new(Mutex::new(vec![burn_at(42)]));
+        let mut cfg = crate::config::tests::cfg_with("");
+        cfg.ton_api_base = provider(burns.clone()).await.parse().unwrap();
+        let db = repo::tests::temp_db().await;
+        let bus = EventBus::new(db.clone(), 16);
+        let route = cfg.routes.iter().next().unwrap();
+        let signed = || async { repo::list_pending_attestations(&db, &[], 10).await.unwrap().len() };
+        let cursor = || async { repo::get_cursor(&db, &route.cursor_key()).await.unwrap() };
+
+        tick(&cfg, &db, &bus).await.unwrap();
+        assert_eq!((signed().await, cursor().await.as_deref()), (1, Some("42")));
+
+        for target in [route.name.as_str(), crate::pause::GLOBAL] {
+            crate::pause::set(&db, target, true, None, "test").await.unwrap();
+            burns.lock().unwrap().push(burn_at(if target == crate::pause::GLOBAL { 44 } else { 43 }));
+            tick(&cfg, &db, &bus).await.unwrap();
+            assert_eq!((signed().await, cursor().await.as_deref()), (1, Some("42")), "paused {target}");
+            crate::pause::set(&db, target, false, None, "test").await.unwrap();
         }
 
-        tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+        // Burns made during either pause are attested after the resume.
+        tick(&cfg, &db, &bus).await.unwrap();
+        assert_eq!((signed().await, cursor().await.as_deref()), (3, Some("44")));
     }
 }
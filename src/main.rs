@@ -1,4 +1,5 @@
 mod config;
+mod amounts;
 mod utils;
 mod errors;
 mod signer;
@@ -6,59 +7,194 @@ mod attestations;
 mod repo;
 mod api;
 mod ingest;
+mod routes;
+mod supervisor;
+mod shutdown;
+mod health;
+mod metrics;
+mod events;
+mod ws;
+mod webhooks;
+mod auth;
+mod pause;
+mod cli;
+mod replay;
+mod ratelimit;
+mod stats;
 
 mod solana;
 mod ton;
 
 use anyhow::Result;
-use tracing::{info, Level};
+use tokio_util::sync::CancellationToken;
+use opentelemetry::{trace::TracerProvider as _, KeyValue};
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::{trace::TracerProvider, Resource};
+use tracing::{info, warn};
 use dotenvy::dotenv;
 
 #[tokio::main]
 async fn main() -> Result<()> {
     dotenv().ok();
-    init_tracing();
 
-    let cfg = config::Cfg::from_env()?;
+    let cfg = config::Cfg::load()?;
+
+    // operator commands run once and exit instead of starting the relayer
+    match cli::Command::parse(std::env::args().skip(1)) {
+        Ok(Some(cmd)) => return cli::run(&cfg, cmd).await,
+        Ok(None) => {}
+        Err(usage) => anyhow::bail!(usage),
+    }
+
+    let tracer_provider = init_tracing(&cfg)?;
+    if let Some(endpoint) = &cfg.otlp_endpoint {
+        info!("exporting spans to {endpoint} as {}", cfg.otel_service_name);
+    }
 
     info!("bridge-relayer starting…");
     info!("Relayer pubkey (hex): {}", hex::encode(cfg.relayer_pubkey));
+    info!("cfg_hash: {}", hex::encode(cfg.cfg_hash));
+    for r in cfg.routes.iter() {
+        info!(
+            "route {}: {} -> {} (dec {}/{}, enabled={})",
+            r.name, r.ton_master, r.sol_mint, r.decimals_ton, r.decimals_sol, r.enabled
+        );
+    }
 
     // DB
-    let db = repo::init_db("sqlite://relayer.db").await?;
+    let db = repo::init_db(&cfg.db_url).await?;
+
+    // persisted event log, fanned out to /events
+    let bus = events::EventBus::new(db.clone(), 512);
 
-    // SSE broadcast
-    let (tx, _rx) = tokio::sync::broadcast::channel::<String>(512);
+    // cancelled on SIGINT/SIGTERM; every worker and the HTTP server watch it
+    let token = CancellationToken::new();
 
-    // spawn ingest (TON → attestation → submit)
+    // supervised workers
+    let sup = supervisor::Supervisor::new(token.clone());
+    {
+        let (cfg, db, bus) = (cfg.clone(), db.clone(), bus.clone());
+        // TON → attestation (queued as pending)
+        sup.spawn("ton_ingest", cfg.restart_policy(), move |shutdown| {
+            ingest::ton::run(cfg.clone(), db.clone(), bus.clone(), shutdown)
+        });
+    }
+    {
+        let (cfg, db, bus) = (cfg.clone(), db.clone(), bus.clone());
+        // pending attestations → Solana
+        sup.spawn("solana_submit", cfg.restart_policy(), move |shutdown| {
+            solana::submit::run(cfg.clone(), db.clone(), bus.clone(), shutdown)
+        });
+    }
+    {
+        let cfg = cfg.clone();
+        // Solana watcher (heartbeat for now)
+        sup.spawn("solana_watcher", cfg.restart_policy(), move |shutdown| {
+            solana::watcher::run(cfg.clone(), shutdown)
+        });
+    }
+    {
+        let (cfg, db, bus) = (cfg.clone(), db.clone(), bus.clone());
+        // events → registered webhooks
+        sup.spawn("webhooks", cfg.restart_policy(), move |shutdown| {
+            webhooks::run(cfg.clone(), db.clone(), bus.clone(), shutdown)
+        });
+    }
+    {
+        let (db, bus) = (db.clone(), bus.clone());
+        // pause/resume switches → `pause` events
+        sup.spawn("pause_watcher", cfg.restart_policy(), move |shutdown| {
+            pause::watch(db.clone(), bus.clone(), shutdown)
+        });
+    }
     {
-        let db_clone = db.clone();
-        let tx_clone = tx.clone();
-        tokio::spawn(async move {
-            if let Err(e) = ingest::ton::run(db_clone, tx_clone).await {
-                tracing::error!("ingest.tn.run error: {e:?}");
-            }
+        let (db, retention) = (db.clone(), cfg.event_retention);
+        // drop /events history past the replay window
+        sup.spawn("event_pruner", cfg.restart_policy(), move |shutdown| {
+            events::prune(db.clone(), retention, shutdown)
         });
     }
 
-    // spawn Solana watcher (your existing heartbeat)
-    tokio::spawn(async move {
-        solana::watcher::run(&cfg).await.ok();
-    });
+    // start HTTP API
+    let grace = cfg.shutdown_grace;
+    let mut server = tokio::spawn(api::serve(cfg, db.clone(), bus, sup.clone(), token.clone()));
 
-    // start HTTP API (port from env or 3000)
-    let bind = std::env::var("API_BIND").unwrap_or_else(|_| "127.0.0.1:3000".into());
-    api::serve(bind, db, tx).await?;
+    let server_res = tokio::select! {
+        _ = shutdown::signal() => None,
+        res = &mut server => Some(res),
+    };
+    info!("shutting down (grace {grace:?})…");
+    token.cancel();
 
-    Ok(())
+    let drain = async {
+        sup.join_all().await;
+        match server_res {
+            Some(res) => res,
+            None => server.await,
+        }
+    };
+    let server_res = match tokio::time::timeout(grace, drain).await {
+        Ok(res) => Some(res),
+        Err(_) => {
+            warn!("shutdown grace period elapsed; abandoning remaining tasks");
+            None
+        }
+    };
+
+    db.close().await;
+    info!("bridge-relayer stopped");
+    if let Some(p) = tracer_provider {
+        if let Err(e) = p.shutdown() {
+            warn!("flushing spans failed: {e}");
+        }
+    }
+
+    match server_res {
+        Some(Ok(res)) => res,
+        Some(Err(join)) => Err(join.into()),
+        None => Ok(()),
+    }
 }
 
-fn init_tracing() {
+/// Log output per `log_format`, plus OTLP span export when an endpoint is
+/// configured. The returned provider must be shut down to flush spans.
+fn init_tracing(cfg: &config::Cfg) -> Result<Option<TracerProvider>> {
+    use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter, Layer};
+
     let filter = std::env::var("RUST_LOG").unwrap_or_else(|_| "info,tower_http=off".into());
-    tracing_subscriber::fmt()
-        .with_env_filter(filter)
-        .with_target(false)
-        .with_ansi(true)
-        .with_max_level(Level::INFO)
+    let fmt = match cfg.log_format {
+        config::LogFormat::Text => tracing_subscriber::fmt::layer().with_target(false).with_ansi(true).boxed(),
+        config::LogFormat::Json => tracing_subscriber::fmt::layer()
+            .json()
+            .with_current_span(true)
+            .with_span_list(false)
+            .boxed(),
+    };
+
+    let provider = match &cfg.otlp_endpoint {
+        Some(endpoint) => {
+            let exporter = opentelemetry_otlp::SpanExporter::builder()
+                .with_tonic()
+                .with_endpoint(endpoint.as_str())
+                .build()?;
+            let resource = Resource::new([KeyValue::new("service.name", cfg.otel_service_name.clone())]);
+            Some(
+                TracerProvider::builder()
+                    .with_batch_exporter(exporter, opentelemetry_sdk::runtime::Tokio)
+                    .with_resource(resource)
+                    .build(),
+            )
+        }
+        None => None,
+    };
+    let otel = provider
+        .as_ref()
+        .map(|p| tracing_opentelemetry::layer().with_tracer(p.tracer("bridge-relayer")));
+
+    tracing_subscriber::registry()
+        .with(fmt)
+        .with(otel)
+        .with(EnvFilter::new(filter))
         .init();
+    Ok(provider)
 }
@@ -0,0 +1,170 @@
+use prometheus::{
+    Encoder, HistogramOpts, HistogramVec, IntCounter, IntCounterVec, IntGauge, IntGaugeVec, Opts, Registry, TextEncoder,
+};
+use std::sync::LazyLock;
+use std::time::Duration;
+
+/// Process-wide metrics, exported by `GET /metrics`.
+///
+/// Kept in a static so call sites deep in the watcher and submitter can
+/// record without threading a handle through every signature.
+pub struct Metrics {
+    registry: Registry,
+    pub burns_ingested: IntCounterVec,
+    pub burns_quarantined: IntCounterVec,
+    /// Labelled by lifecycle step: created, submitted, confirmed (transaction
+    /// finalized), failed.
+    pub attestations: IntCounterVec,
+    pub provider_requests: IntCounterVec,
+    pub provider_latency: HistogramVec,
+    pub cursor_lag_lt: IntGaugeVec,
+    pub cursor_lag_seconds: IntGaugeVec,
+    pub submission_queue_depth: IntGauge,
+    pub sse_subscribers: IntGauge,
+    pub ws_subscribers: IntGauge,
+    /// Labelled by event type.
+    pub events: IntCounterVec,
+    /// Labelled by result: delivered, retry, dead.
+    pub webhook_deliveries: IntCounterVec,
+    /// Public API requests refused by the per-client rate limit.
+    pub api_rate_limited: IntCounter,
+}
+
+pub static METRICS: LazyLock<Metrics> = LazyLock::new(Metrics::new);
+
+impl Metrics {
+    fn new() -> Self {
+        let registry = Registry::new_custom(Some("relayer".into()), None).expect("metrics registry");
+
+        let burns_ingested = IntCounterVec::new(
+            Opts::new("burns_ingested_total", "TON burns stored and attested"),
+            &["route"],
+        )
+        .expect("metric");
+        let burns_quarantined = IntCounterVec::new(
+            Opts::new("burns_quarantined_total", "TON burns held back in quarantine"),
+            &["route", "code"],
+        )
+        .expect("metric");
+        let attestations = IntCounterVec::new(
+            Opts::new("attestations_total", "Attestations by lifecycle step"),
+            &["status"],
+        )
+        .expect("metric");
+        let provider_requests = IntCounterVec::new(
+            Opts::new("provider_requests_total", "Requests to TON/Solana providers by result"),
+            &["provider", "endpoint", "status"],
+        )
+        .expect("metric");
+        let provider_latency = HistogramVec::new(
+            HistogramOpts::new("provider_request_duration_seconds", "Provider request latency")
+                .buckets(vec![0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0]),
+            &["provider", "endpoint"],
+        )
+        .expect("metric");
+        let cursor_lag_lt = IntGaugeVec::new(
+            Opts::new("cursor_lag_lt", "Latest provider lt minus the route cursor"),
+            &["route"],
+        )
+        .expect("metric");
+        let cursor_lag_seconds = IntGaugeVec::new(
+            Opts::new("cursor_lag_seconds", "Age of the oldest burn not yet behind the route cursor"),
+            &["route"],
+        )
+        .expect("metric");
+        let submission_queue_depth =
+            IntGauge::new("submission_queue_depth", "Attestations waiting for Solana submission").expect("metric");
+        let sse_subscribers = IntGauge::new("sse_subscribers", "Connected /events clients").expect("metric");
+        let ws_subscribers = IntGauge::new("ws_subscribers", "Connected /ws clients").expect("metric");
+
+        let events = IntCounterVec::new(Opts::new("events_total", "Events published on the event bus"), &["type"])
+            .expect("metric");
+
+        let webhook_deliveries = IntCounterVec::new(
+            Opts::new("webhook_deliveries_total", "Webhook delivery attempts by result"),
+            &["result"],
+        )
+        .expect("metric");
+
+        let api_rate_limited =
+            IntCounter::new("api_rate_limited_total", "API requests refused by the rate limit").expect("metric");
+        registry.register(Box::new(api_rate_limited.clone())).expect("register metric");
+
+        for c in [&burns_ingested, &burns_quarantined, &attestations, &provider_requests, &events, &webhook_deliveries] {
+            registry.register(Box::new(c.clone())).expect("register metric");
+        }
+        for g in [&cursor_lag_lt, &cursor_lag_seconds] {
+            registry.register(Box::new(g.clone())).expect("register metric");
+        }
+        registry.register(Box::new(provider_latency.clone())).expect("register metric");
+        for g in [&submission_queue_depth, &sse_subscribers, &ws_subscribers] {
+            registry.register(Box::new(g.clone())).expect("register metric");
+        }
+
+        // Export every lifecycle step from the start so rate() works before the first event.
+        for status in ["created", "submitted", "confirmed", "failed"] {
+            attestations.with_label_values(&[status]);
+        }
+
+        Self {
+            registry,
+            burns_ingested,
+            burns_quarantined,
+            attestations,
+            provider_requests,
+            provider_latency,
+            cursor_lag_lt,
+            cursor_lag_seconds,
+            submission_queue_depth,
+            sse_subscribers,
+            ws_subscribers,
+            events,
+            webhook_deliveries,
+            api_rate_limited,
+        }
+    }
+
+    /// `status` is the HTTP code, or `error` when no response arrived.
+    pub fn provider_request(&self, provider: &str, endpoint: &str, status: &str, elapsed: Duration) {
+        self.provider_requests.with_label_values(&[provider, endpoint, status]).inc();
+        self.provider_latency.with_label_values(&[provider, endpoint]).observe(elapsed.as_secs_f64());
+    }
+
+    /// Prometheus text exposition format.
+    pub fn render(&self) -> String {
+        let mut buf = Vec::new();
+        TextEncoder::new().encode(&self.registry.gather(), &mut buf).expect("encode metrics");
+        String::from_utf8(buf).expect("metrics are utf-8")
+    }
+}
+
+/// Keeps a subscriber gauge accurate for as long as a stream holds it.
+pub struct SubscriberGuard(&'static IntGauge);
+
+impl SubscriberGuard {
+    pub fn acquire(gauge: &'static IntGauge) -> Self {
+        gauge.inc();
+        Self(gauge)
+    }
+}
+
+impl Drop for SubscriberGuard {
+    fn drop(&mut self) {
+        self.0.dec();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_namespaced_series() {
+        METRICS.burns_ingested.with_label_values(&["TEST"]).inc();
+        METRICS.provider_request("toncenter", "jetton/burns", "200", Duration::from_millis(120));
+        let text = METRICS.render();
+        assert!(text.contains(r#"relayer_burns_ingested_total{route="TEST"}"#), "{text}");
+        assert!(text.contains(r#"relayer_attestations_total{status="confirmed"} 0"#));
+        assert!(text.contains("relayer_provider_request_duration_seconds_bucket"));
+    }
+}
@@ -0,0 +1,139 @@
+use serde::Serialize;
+use sqlx::SqlitePool;
+use std::collections::BTreeMap;
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
+
+use crate::config::Cfg;
+use crate::errors::Result;
+use crate::events::{EventBus, PauseEvent, RelayerEvent};
+use crate::repo::{self, PauseRow};
+
+/// Pause target covering every route.
+pub const GLOBAL: &str = "*";
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Pause {
+    pub reason: Option<String>,
+    pub by: String,
+    /// Unix time of the pause.
+    pub since: i64,
+}
+
+/// The switches currently on. A route is paused when it or everything is.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct PauseState {
+    pub global: Option<Pause>,
+    pub routes: BTreeMap<String, Pause>,
+}
+
+impl PauseState {
+    /// Read from the database on every call, so a switch flipped by another
+    /// process (the CLI) takes effect on the next check.
+    pub async fn load(db: &SqlitePool) -> Result<Self> {
+        Ok(Self::from_rows(repo::list_pauses(db).await?))
+    }
+
+    fn from_rows(rows: Vec<PauseRow>) -> Self {
+        let mut state = Self::default();
+        for r in rows.into_iter().filter(|r| r.paused) {
+            let p = Pause { reason: r.reason, by: r.changed_by, since: r.changed_at };
+            if r.target == GLOBAL {
+                state.global = Some(p);
+            } else {
+                state.routes.insert(r.target, p);
+            }
+        }
+        state
+    }
+
+    pub fn route_paused(&self, route: &str) -> bool {
+        self.global.is_some() || self.routes.contains_key(route)
+    }
+}
+
+/// Switch name for `route`, or the global one for `None`. Unknown route
+/// names are rejected so a typo cannot look like a successful pause.
+pub fn target<'a>(cfg: &Cfg, route: Option<&'a str>) -> std::result::Result<&'a str, String> {
+    match route {
+        None => Ok(GLOBAL),
+        Some(r) if cfg.routes.iter().any(|route| route.name == r) => Ok(r),
+        Some(r) => Err(format!("no route named {r:?}")),
+    }
+}
+
+/// Flip one switch (see [`target`]) and return the resulting state.
+pub async fn set(db: &SqlitePool, target: &str, paused: bool, reason: Option<&str>, by: &str) -> Result<PauseState> {
+    repo::set_pause(db, target, paused, reason, by).await?;
+    let what = if target == GLOBAL { "relaying" } else { target };
+    let verb = if paused { "paused" } else { "resumed" };
+    tracing::warn!("{what} {verb} by {by}{}", reason.map(|r| format!(": {r}")).unwrap_or_default());
+    PauseState::load(db).await
+}
+
+/// Publish changes that have no `pause` event yet, oldest first.
+async fn announce(db: &SqlitePool, bus: &EventBus) -> Result<()> {
+    for r in repo::list_unannounced_pauses(db).await? {
+        bus.emit(RelayerEvent::Pause(PauseEvent {
+            route: (r.target != GLOBAL).then(|| r.target.clone()),
+            paused: r.paused,
+            reason: r.reason,
+            by: r.changed_by,
+        }))
+        .await;
+        repo::mark_pause_announced(db, &r.target, r.version).await?;
+    }
+    Ok(())
+}
+
+/// Worker turning switch changes into `pause` events. Changes are picked up
+/// from the table rather than from the API, so CLI changes made while the
+/// relayer runs, or while it was down, are announced too.
+pub async fn watch(db: SqlitePool, bus: EventBus, shutdown: CancellationToken) -> Result<()> {
+    while !shutdown.is_cancelled() {
+        announce(&db, &bus).await?;
+        tokio::select! {
+            _ = tokio::time::sleep(Duration::from_secs(1)) => {}
+            _ = shutdown.cancelled() => {}
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn switches_combine_and_each_change_is_announced_once() {
+        let db = repo::tests::temp_db().await;
+        let bus = EventBus::new(db.clone(), 16);
+        let cfg = crate::config::tests::cfg_with("");
+        let route = cfg.routes.iter().next().unwrap().name.clone();
+
+        assert!(target(&cfg, Some("nope")).is_err());
+        assert_eq!(target(&cfg, None), Ok(GLOBAL));
+
+        let t = target(&cfg, Some(&route)).unwrap();
+        let state = set(&db, t, true, Some("mint drained"), "ops").await.unwrap();
+        assert!(state.global.is_none() && state.route_paused(&route) && !state.route_paused("other"));
+
+        set(&db, GLOBAL, true, None, "ops").await.unwrap();
+        assert!(PauseState::load(&db).await.unwrap().route_paused("other"));
+        let state = set(&db, GLOBAL, false, None, "ops").await.unwrap();
+        assert!(state.global.is_none() && state.route_paused(&route));
+
+        announce(&db, &bus).await.unwrap();
+        announce(&db, &bus).await.unwrap();
+        let events: Vec<_> = bus.replay(0, 10).await.unwrap().into_iter().map(|r| r.event).collect();
+        // Only the latest state of each target is announced.
+        assert_eq!(events.len(), 2);
+        assert!(events.contains(&RelayerEvent::Pause(PauseEvent {
+            route: None,
+            paused: false,
+            reason: None,
+            by: "ops".into(),
+        })));
+        assert!(events.iter().any(|e| e.route() == Some(route.as_str())));
+    }
+}
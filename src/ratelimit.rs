@@ -0,0 +1,123 @@
+use axum::{
+    extract::{ConnectInfo, Request, State},
+    http::{header, HeaderValue, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::errors::ApiError;
+
+/// Per-client request budget: `per_minute` sustained, up to `burst` at once.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimit {
+    pub per_minute: u32,
+    pub burst: u32,
+}
+
+/// Buckets kept before idle (full) ones are dropped.
+const MAX_CLIENTS: usize = 10_000;
+
+/// Token bucket per client IP. In-process only: each relayer instance
+/// enforces the limit on its own.
+pub struct Limiter {
+    limit: RateLimit,
+    /// Header holding the client address when behind a proxy, e.g.
+    /// `x-forwarded-for`. Its last entry is used: that is the one our proxy
+    /// appended, while earlier ones come from the client and can be forged.
+    client_ip_header: Option<String>,
+    buckets: Mutex<HashMap<IpAddr, Bucket>>,
+}
+
+struct Bucket {
+    tokens: f64,
+    at: Instant,
+}
+
+impl Limiter {
+    pub fn new(limit: RateLimit, client_ip_header: Option<String>) -> Self {
+        Self { limit, client_ip_header, buckets: Mutex::new(HashMap::new()) }
+    }
+
+    /// Take one token for `client`, or say how long until one is available.
+    fn take(&self, client: IpAddr, now: Instant) -> Result<(), Duration> {
+        let rate = self.limit.per_minute as f64 / 60.0;
+        let burst = self.limit.burst as f64;
+        let mut buckets = self.buckets.lock().unwrap();
+        if buckets.len() >= MAX_CLIENTS && !buckets.contains_key(&client) {
+            buckets.retain(|_, b| b.tokens + now.duration_since(b.at).as_secs_f64() * rate < burst);
+        }
+        let b = buckets.entry(client).or_insert(Bucket { tokens: burst, at: now });
+        b.tokens = (b.tokens + now.duration_since(b.at).as_secs_f64() * rate).min(burst);
+        b.at = now;
+        if b.tokens >= 1.0 {
+            b.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - b.tokens) / rate))
+        }
+    }
+
+    fn client(&self, req: &Request) -> IpAddr {
+        let forwarded = self
+            .client_ip_header
+            .as_deref()
+            .and_then(|h| req.headers().get(h))
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.rsplit(',').next())
+            .and_then(|v| v.trim().parse().ok());
+        forwarded
+            .or_else(|| req.extensions().get::<ConnectInfo<SocketAddr>>().map(|c| c.0.ip()))
+            .unwrap_or(IpAddr::V4(Ipv4Addr::UNSPECIFIED))
+    }
+}
+
+/// Middleware for the public routes: 429 with `Retry-After` once a client
+/// has spent its budget.
+pub async fn limit(State(limiter): State<Arc<Limiter>>, req: Request, next: Next) -> Response {
+    let client = limiter.client(&req);
+    match limiter.take(client, Instant::now()) {
+        Ok(()) => next.run(req).await,
+        Err(wait) => {
+            crate::metrics::METRICS.api_rate_limited.inc();
+            let secs = wait.as_secs() + 1;
+            let mut res = ApiError::new(StatusCode::TOO_MANY_REQUESTS, format!("rate limit exceeded; retry in {secs}s"))
+                .into_response();
+            res.headers_mut().insert(header::RETRY_AFTER, HeaderValue::from(secs));
+            res
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn buckets_refill_per_client() {
+        let l = Limiter::new(RateLimit { per_minute: 60, burst: 2 }, None);
+        let (a, b) = ("10.0.0.1".parse().unwrap(), "10.0.0.2".parse().unwrap());
+        let t0 = Instant::now();
+        assert!(l.take(a, t0).is_ok());
+        assert!(l.take(a, t0).is_ok());
+        let wait = l.take(a, t0).unwrap_err();
+        assert!(wait > Duration::from_millis(900) && wait <= Duration::from_secs(1));
+        assert!(l.take(b, t0).is_ok());
+        assert!(l.take(a, t0 + Duration::from_secs(1)).is_ok());
+        assert!(l.take(a, t0 + Duration::from_secs(1)).is_err());
+    }
+
+    #[test]
+    fn forwarded_client_is_the_last_hop() {
+        let l = Limiter::new(RateLimit { per_minute: 60, burst: 2 }, Some("x-forwarded-for".into()));
+        let req = |xff: &str| Request::builder().header("x-forwarded-for", xff).body(Default::default()).unwrap();
+        let proxy_saw: IpAddr = "203.0.113.7".parse().unwrap();
+        assert_eq!(l.client(&req("203.0.113.7")), proxy_saw);
+        // A client cannot pick its bucket by sending its own header.
+        assert_eq!(l.client(&req("10.9.9.9, 203.0.113.7")), proxy_saw);
+        assert_eq!(l.client(&req("garbage")), IpAddr::V4(Ipv4Addr::UNSPECIFIED));
+    }
+}
@@ -0,0 +1,294 @@
+use axum::http::StatusCode;
+use serde::Serialize;
+use serde_json::Value;
+use sqlx::SqlitePool;
+use std::collections::BTreeMap;
+use tracing::info;
+
+use crate::attestations::{payload_json, TonToSolAttestation};
+use crate::config::Cfg;
+use crate::errors::{ApiError, RelayerError};
+use crate::events::EventBus;
+use crate::ingest::ton::{b64_to_32, build_attestation, sign_attestation, store_attestation};
+use crate::pause::PauseState;
+use crate::repo::{self, AttDetail};
+use crate::ton::address::TonAddress;
+use crate::ton::watcher::TonBurn;
+
+#[derive(Debug, thiserror::Error)]
+pub enum ReplayError {
+    #[error("{0}")]
+    NotFound(String),
+    /// The attestation's status does not allow the action.
+    #[error("{0}")]
+    Conflict(String),
+    /// Stored data cannot be used as is.
+    #[error("{0}")]
+    Unusable(String),
+    #[error(transparent)]
+    Relayer(#[from] RelayerError),
+}
+
+impl ReplayError {
+    pub fn status_code(&self) -> StatusCode {
+        match self {
+            ReplayError::NotFound(_) => StatusCode::NOT_FOUND,
+            ReplayError::Conflict(_) => StatusCode::CONFLICT,
+            ReplayError::Unusable(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            ReplayError::Relayer(e) => e.status_code(),
+        }
+    }
+}
+
+impl From<ReplayError> for ApiError {
+    fn from(e: ReplayError) -> Self {
+        match e {
+            ReplayError::Relayer(e) => e.into(),
+            e => ApiError::new(e.status_code(), e),
+        }
+    }
+}
+
+impl From<sqlx::Error> for ReplayError {
+    fn from(e: sqlx::Error) -> Self {
+        ReplayError::Relayer(e.into())
+    }
+}
+
+/// What a resubmit or rebuild does. A dry run returns the same plan and
+/// changes nothing.
+#[derive(Debug, Serialize)]
+pub struct Plan {
+    pub action: &'static str,
+    pub dry_run: bool,
+    pub src_tx_hash_b64: Option<String>,
+    /// The attestation acted on or replaced: id, status and payload (Borsh,
+    /// hash and decoded fields).
+    pub current: Option<Value>,
+    /// Payload of the rebuilt attestation; resubmits send `current` as is.
+    pub rebuilt: Option<Value>,
+    pub hash_changed: bool,
+    /// Decoded fields that differ between `current` and `rebuilt`, as `{from, to}`.
+    pub diff: BTreeMap<String, Value>,
+}
+
+fn current_json(a: &AttDetail, testnet: bool) -> Value {
+    serde_json::json!({ "id": a.id, "status": a.status, "payload": payload_json(&a.payload_borsh, testnet) })
+}
+
+fn field_diff(from: &Value, to: &Value) -> BTreeMap<String, Value> {
+    let (Some(from), Some(to)) = (from.as_object(), to.as_object()) else {
+        return BTreeMap::new();
+    };
+    to.iter()
+        .filter(|(k, v)| from.get(*k) != Some(*v))
+        .map(|(k, v)| (k.clone(), serde_json::json!({ "from": from.get(k), "to": v })))
+        .collect()
+}
+
+/// Queue an attestation for submission again, unchanged. `failed` ones and
+/// rows from before the submission queue qualify; a `submitted` one only
+/// with `force`, since only the program's replay protection then stands
+/// between a resend and a second mint.
+pub async fn resubmit(
+    cfg: &Cfg,
+    db: &SqlitePool,
+    hash_hex: &str,
+    force: bool,
+    dry_run: bool,
+    by: &str,
+) -> Result<Plan, ReplayError> {
+    let att = repo::get_attestation_by_hash(db, hash_hex)
+        .await?
+        .ok_or_else(|| ReplayError::NotFound(format!("attestation {hash_hex} not found")))?;
+    match att.status.as_deref() {
+        None | Some("failed") => {}
+        Some("submitted") if force => {}
+        Some("submitted") => {
+            return Err(ReplayError::Conflict(format!(
+                "attestation {hash_hex} was already submitted; pass force=true to send it again"
+            )))
+        }
+        Some(s) => return Err(ReplayError::Conflict(format!("attestation {hash_hex} is {s}"))),
+    }
+    if let Err(e) = TonToSolAttestation::decode(&att.payload_borsh) {
+        return Err(ReplayError::Unusable(format!("stored payload does not decode ({e}); rebuild it from its burn")));
+    }
+
+    if !dry_run {
+        if !repo::requeue_attestation(db, att.id, att.status.as_deref()).await? {
+            return Err(ReplayError::Conflict(format!("attestation {hash_hex} changed status, try again")));
+        }
+        info!("attestation {hash_hex} requeued for submission by {by}");
+    }
+    Ok(Plan {
+        action: "resubmit",
+        dry_run,
+        src_tx_hash_b64: att.src_tx_hash_b64.clone(),
+        current: Some(current_json(&att, cfg.ton_testnet())),
+        rebuilt: None,
+        hash_changed: false,
+        diff: BTreeMap::new(),
+    })
+}
+
+/// Build the attestation for a stored burn again under the current config,
+/// e.g. after a route fix, and queue it in place of the latest one. Route
+/// limits are waived and dust is truncated, as for a quarantine release.
+///
+/// The replaced attestation becomes `superseded`. One that was submitted
+/// cannot be replaced, and a `pending` one only while its route is paused,
+/// so it cannot be in flight.
+pub async fn rebuild(
+    cfg: &Cfg,
+    db: &SqlitePool,
+    bus: &EventBus,
+    src_tx: &str,
+    dry_run: bool,
+    by: &str,
+) -> Result<Plan, ReplayError> {
+    let b = repo::get_burn(db, src_tx)
+        .await?
+        .ok_or_else(|| ReplayError::NotFound(format!("burn {src_tx} not found")))?;
+    let route = b
+        .jetton_master_raw
+        .parse::<TonAddress>()
+        .ok()
+        .and_then(|m| cfg.routes.get(&m))
+        .ok_or_else(|| ReplayError::Unusable(format!("no route configured for jetton master {}", b.jetton_master_raw)))?;
+    let amount_raw: u128 = b
+        .amount_raw
+        .parse()
+        .map_err(|e| ReplayError::Unusable(format!("stored amount {:?}: {e}", b.amount_raw)))?;
+    let normalized = match route.normalize(amount_raw) {
+        Ok(n) => n,
+        Err(e) => crate::amounts::ton_to_sol(amount_raw, route.decimals_ton, route.decimals_sol)
+            .ok()
+            .filter(|n| n.amount_sol > 0)
+            .ok_or_else(|| ReplayError::Unusable(format!("amount: {e}")))?,
+    };
+    let burn = TonBurn {
+        tx_hash_b64: b.tx_hash_b64.clone(),
+        lt: b.lt as u64,
+        utime: b.utime as u64,
+        jetton_master_raw: b.jetton_master_raw,
+        owner_raw: b.owner_raw,
+        jetton_wallet_raw: b.jetton_wallet_raw,
+        amount_raw: b.amount_raw,
+        custom_payload: b.custom_payload,
+    };
+    let att = build_attestation(cfg, route, &burn, b64_to_32(&burn.tx_hash_b64)?, amount_raw, &normalized);
+    let bytes = borsh::to_vec(&att).map_err(|e| RelayerError::Invariant(format!("borsh serialize: {e}")))?;
+
+    let testnet = cfg.ton_testnet();
+    let existing = repo::get_attestation_by_src_tx(db, src_tx).await?;
+    let rebuilt = payload_json(&bytes, testnet);
+    let current = existing.as_ref().map(|a| current_json(a, testnet));
+    let hash_changed = existing.as_ref().is_none_or(|a| a.payload_hash_hex != rebuilt["hash_hex"]);
+    let diff = current
+        .as_ref()
+        .map(|c| field_diff(&c["payload"]["decoded"], &rebuilt["decoded"]))
+        .unwrap_or_default();
+
+    if !dry_run {
+        if let Some(a) = &existing {
+            if !hash_changed {
+                return Err(ReplayError::Conflict(format!(
+                    "rebuilt attestation is identical to {}; resubmit it instead",
+                    a.payload_hash_hex
+                )));
+            }
+            match a.status.as_deref() {
+                Some("submitted") => {
+                    return Err(ReplayError::Conflict(format!(
+                        "attestation {} was already submitted; a rebuild would mint twice",
+                        a.payload_hash_hex
+                    )))
+                }
+                Some("pending") if !PauseState::load(db).await?.route_paused(&route.name) => {
+                    return Err(ReplayError::Conflict(format!(
+                        "attestation {} is pending and may be in flight; pause route {} first",
+                        a.payload_hash_hex, route.name
+                    )))
+                }
+                // Retire the old one first: if anything below fails, nothing is queued twice.
+                Some("superseded") => {}
+                status => {
+                    if !repo::supersede_attestation(db, a.id, status).await? {
+                        return Err(ReplayError::Conflict(format!(
+                            "attestation {} changed status, try again",
+                            a.payload_hash_hex
+                        )));
+                    }
+                }
+            }
+        }
+        let signed = sign_attestation(cfg, &att)?;
+        store_attestation(cfg, db, bus, route, &burn, &signed, &normalized).await?;
+        info!("burn {src_tx} re-attested as {} by {by}", signed.hash_hex);
+    }
+    Ok(Plan {
+        action: "rebuild",
+        dry_run,
+        src_tx_hash_b64: Some(src_tx.to_string()),
+        current,
+        rebuilt: Some(rebuilt),
+        hash_changed,
+        diff,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn stored_burn(cfg: &Cfg, db: &SqlitePool) -> String {
+        let route = cfg.routes.iter().next().unwrap();
+        let tx = base64::Engine::encode(&base64::engine::general_purpose::STANDARD, [7u8; 32]);
+        let master = route.ton_master.to_raw();
+        repo::insert_burn(db, &repo::tests::burn(&tx, 10, 1_700_000_000, &master, "0:AA", "5000000000")).await.unwrap();
+        tx
+    }
+
+    #[tokio::test]
+    async fn rebuild_diffs_against_the_current_attestation_then_resubmit_requeues() {
+        let mut cfg = crate::config::tests::cfg_with("");
+        let db = repo::tests::temp_db().await;
+        let bus = EventBus::new(db.clone(), 16);
+        let tx = stored_burn(&cfg, &db).await;
+
+        // No attestation yet: a dry run only shows what would be built.
+        let plan = rebuild(&cfg, &db, &bus, &tx, true, "ops").await.unwrap();
+        assert!(plan.current.is_none() && plan.hash_changed);
+        assert!(repo::get_attestation_by_src_tx(&db, &tx).await.unwrap().is_none());
+        let first = rebuild(&cfg, &db, &bus, &tx, false, "ops").await.unwrap();
+        let first_hash = first.rebuilt.unwrap()["hash_hex"].as_str().unwrap().to_string();
+        assert!(matches!(rebuild(&cfg, &db, &bus, &tx, false, "ops").await, Err(ReplayError::Conflict(_))));
+
+        cfg.cfg_hash = [9u8; 32];
+        let plan = rebuild(&cfg, &db, &bus, &tx, true, "ops").await.unwrap();
+        assert!(plan.hash_changed);
+        assert_eq!(plan.diff.keys().collect::<Vec<_>>(), vec!["cfg_hash"]);
+        assert_eq!(plan.diff["cfg_hash"]["to"], hex::encode([9u8; 32]));
+        // Still pending and the route is running, so it may be in flight.
+        assert!(matches!(rebuild(&cfg, &db, &bus, &tx, false, "ops").await, Err(ReplayError::Conflict(_))));
+
+        let id = repo::get_attestation_by_hash(&db, &first_hash).await.unwrap().unwrap().id;
+        repo::record_submission(&db, id, "failed", Some("rejected"), None).await.unwrap();
+        let plan = rebuild(&cfg, &db, &bus, &tx, false, "ops").await.unwrap();
+        let second_hash = plan.rebuilt.unwrap()["hash_hex"].as_str().unwrap().to_string();
+        let old = repo::get_attestation_by_hash(&db, &first_hash).await.unwrap().unwrap();
+        assert_eq!(old.status.as_deref(), Some("superseded"));
+        let new = repo::get_attestation_by_src_tx(&db, &tx).await.unwrap().unwrap();
+        assert_eq!((new.payload_hash_hex.as_str(), new.status.as_deref()), (second_hash.as_str(), Some("pending")));
+
+        assert!(matches!(resubmit(&cfg, &db, &second_hash, false, false, "ops").await, Err(ReplayError::Conflict(_))));
+        repo::record_submission(&db, new.id, "failed", Some("rpc"), None).await.unwrap();
+        let plan = resubmit(&cfg, &db, &second_hash, false, true, "ops").await.unwrap();
+        assert_eq!(plan.current.unwrap()["status"], "failed");
+        resubmit(&cfg, &db, &second_hash, false, false, "ops").await.unwrap();
+        let new = repo::get_attestation_by_hash(&db, &second_hash).await.unwrap().unwrap();
+        assert_eq!((new.status.as_deref(), new.attempts), (Some("pending"), 0));
+        assert!(matches!(resubmit(&cfg, &db, "00", false, true, "ops").await, Err(ReplayError::NotFound(_))));
+    }
+}
@@ -1,5 +1,6 @@
-use anyhow::Result;
-use sqlx::{Sqlite, SqlitePool, Pool};
+use sqlx::{Executor, Pool, QueryBuilder, Sqlite, SqliteConnection, SqlitePool};
+
+use crate::errors::Result;
 
 pub async fn init_db(url: &str) -> Result<SqlitePool> {
     let pool = Pool::<Sqlite>::connect(url).await?;
@@ -26,9 +27,176 @@ pub async fn init_db(url: &str) -> Result<SqlitePool> {
             payload_borsh BLOB,
             payload_hash_hex TEXT,
             src_tx_hash_b64 TEXT,
+            amount_dst TEXT,
+            dust_raw TEXT,
+            status TEXT,
+            attempts INTEGER NOT NULL DEFAULT 0,
+            last_error TEXT,
+            sol_signature TEXT,
+            confirmed_at INTEGER,
+            confirmed_slot INTEGER,
+            updated_at INTEGER,
             created_at INTEGER DEFAULT (strftime('%s','now'))
         );
     "#).execute(&pool).await?;
+    ensure_column(&pool, "attestations", "amount_dst", "TEXT").await?;
+    ensure_column(&pool, "attestations", "dust_raw", "TEXT").await?;
+    // Rows created before the submission queue existed keep a NULL status and are never re-sent.
+    ensure_column(&pool, "attestations", "status", "TEXT").await?;
+    ensure_column(&pool, "attestations", "attempts", "INTEGER NOT NULL DEFAULT 0").await?;
+    ensure_column(&pool, "attestations", "last_error", "TEXT").await?;
+    ensure_column(&pool, "attestations", "updated_at", "INTEGER").await?;
+    ensure_column(&pool, "attestations", "sol_signature", "TEXT").await?;
+    // Set once the submitted transaction is finalized on Solana.
+    ensure_column(&pool, "attestations", "confirmed_at", "INTEGER").await?;
+    ensure_column(&pool, "attestations", "confirmed_slot", "INTEGER").await?;
+    // Filters used by the list endpoints; the rowid is implicit in each index,
+    // so `col = ? AND id < ? ORDER BY id DESC` is served without a sort.
+    for ddl in [
+        "CREATE INDEX IF NOT EXISTS attestations_status ON attestations(status)",
+        "CREATE INDEX IF NOT EXISTS attestations_hash ON attestations(payload_hash_hex)",
+        "CREATE INDEX IF NOT EXISTS attestations_kind ON attestations(kind)",
+        "CREATE INDEX IF NOT EXISTS attestations_src_tx ON attestations(src_tx_hash_b64)",
+        "CREATE INDEX IF NOT EXISTS attestations_created_at ON attestations(created_at)",
+        "CREATE INDEX IF NOT EXISTS burns_owner ON burns(owner_raw COLLATE NOCASE)",
+        "CREATE INDEX IF NOT EXISTS burns_jetton_master ON burns(jetton_master_raw COLLATE NOCASE)",
+        "CREATE INDEX IF NOT EXISTS burns_utime ON burns(utime)",
+    ] {
+        sqlx::query(ddl).execute(&pool).await?;
+    }
+
+    sqlx::query(r#"
+        CREATE TABLE IF NOT EXISTS quarantine(
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            qkey TEXT NOT NULL UNIQUE,
+            tx_hash_b64 TEXT,
+            jetton_master_raw TEXT NOT NULL,
+            reason_code TEXT NOT NULL,
+            reason TEXT NOT NULL,
+            releasable INTEGER NOT NULL,
+            status TEXT NOT NULL DEFAULT 'held',
+            raw_json TEXT NOT NULL,
+            created_at INTEGER NOT NULL DEFAULT (strftime('%s','now')),
+            updated_at INTEGER NOT NULL DEFAULT (strftime('%s','now'))
+        );
+    "#).execute(&pool).await?;
+
+    sqlx::query(r#"
+        CREATE TABLE IF NOT EXISTS signatures(
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            attestation_id INTEGER NOT NULL REFERENCES attestations(id),
+            signer_hex TEXT NOT NULL,
+            signature_hex TEXT NOT NULL,
+            created_at INTEGER NOT NULL DEFAULT (strftime('%s','now')),
+            UNIQUE(attestation_id, signer_hex)
+        );
+    "#).execute(&pool).await?;
+
+    // One row per submission attempt; `attestations` only keeps the latest outcome.
+    sqlx::query(r#"
+        CREATE TABLE IF NOT EXISTS submissions(
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            attestation_id INTEGER NOT NULL REFERENCES attestations(id),
+            attempt INTEGER NOT NULL,
+            status TEXT NOT NULL,
+            error TEXT,
+            sol_signature TEXT,
+            created_at INTEGER NOT NULL DEFAULT (strftime('%s','now'))
+        );
+    "#).execute(&pool).await?;
+    sqlx::query("CREATE INDEX IF NOT EXISTS submissions_attestation ON submissions(attestation_id)")
+        .execute(&pool).await?;
+
+    // Stream log behind `/events`; ids are what clients resume from, so
+    // AUTOINCREMENT keeps them from being reused after pruning.
+    sqlx::query(r#"
+        CREATE TABLE IF NOT EXISTS events(
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL,
+            data TEXT NOT NULL,
+            created_at INTEGER NOT NULL DEFAULT (strftime('%s','now'))
+        );
+    "#).execute(&pool).await?;
+    sqlx::query("CREATE INDEX IF NOT EXISTS events_created_at ON events(created_at)")
+        .execute(&pool).await?;
+
+    // Webhook subscriptions. `types` is a comma-separated list of event
+    // types; empty means all of them.
+    sqlx::query(r#"
+        CREATE TABLE IF NOT EXISTS webhooks(
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            url TEXT NOT NULL,
+            secret TEXT NOT NULL,
+            types TEXT NOT NULL DEFAULT '',
+            enabled INTEGER NOT NULL DEFAULT 1,
+            created_at INTEGER NOT NULL DEFAULT (strftime('%s','now'))
+        );
+    "#).execute(&pool).await?;
+
+    // Outstanding deliveries; a row is deleted once the receiver accepts it.
+    // `payload` is the exact body, so pruning the event log does not lose it.
+    sqlx::query(r#"
+        CREATE TABLE IF NOT EXISTS webhook_deliveries(
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            webhook_id INTEGER NOT NULL REFERENCES webhooks(id),
+            event_id INTEGER NOT NULL,
+            event_name TEXT NOT NULL,
+            payload TEXT NOT NULL,
+            attempts INTEGER NOT NULL DEFAULT 0,
+            next_attempt_at INTEGER NOT NULL DEFAULT (strftime('%s','now')),
+            last_error TEXT,
+            last_status INTEGER,
+            created_at INTEGER NOT NULL DEFAULT (strftime('%s','now'))
+        );
+    "#).execute(&pool).await?;
+    sqlx::query("CREATE INDEX IF NOT EXISTS webhook_deliveries_due ON webhook_deliveries(next_attempt_at)")
+        .execute(&pool).await?;
+
+    // Deliveries that used up their attempts, kept until replayed.
+    sqlx::query(r#"
+        CREATE TABLE IF NOT EXISTS webhook_dead_letters(
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            webhook_id INTEGER NOT NULL,
+            event_id INTEGER NOT NULL,
+            event_name TEXT NOT NULL,
+            payload TEXT NOT NULL,
+            attempts INTEGER NOT NULL,
+            last_error TEXT,
+            last_status INTEGER,
+            created_at INTEGER NOT NULL,
+            failed_at INTEGER NOT NULL DEFAULT (strftime('%s','now'))
+        );
+    "#).execute(&pool).await?;
+
+    // One row per admin API call, including rejected ones.
+    sqlx::query(r#"
+        CREATE TABLE IF NOT EXISTS admin_audit(
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            at INTEGER NOT NULL DEFAULT (strftime('%s','now')),
+            token_name TEXT,
+            scope TEXT,
+            method TEXT NOT NULL,
+            path TEXT NOT NULL,
+            status INTEGER NOT NULL,
+            remote_addr TEXT
+        );
+    "#).execute(&pool).await?;
+
+    // Operator pause switches: `target` is `*` for everything or a route
+    // name. Rows are kept on resume so the last change stays visible;
+    // `version` grows on every change; `announced` is the last version
+    // published as an event.
+    sqlx::query(r#"
+        CREATE TABLE IF NOT EXISTS pauses(
+            target TEXT PRIMARY KEY,
+            paused INTEGER NOT NULL,
+            reason TEXT,
+            changed_by TEXT NOT NULL,
+            changed_at INTEGER NOT NULL DEFAULT (strftime('%s','now')),
+            version INTEGER NOT NULL DEFAULT 1,
+            announced INTEGER NOT NULL DEFAULT 0
+        );
+    "#).execute(&pool).await?;
 
     sqlx::query(r#"
         CREATE TABLE IF NOT EXISTS cursors(
@@ -40,34 +208,195 @@ pub async fn init_db(url: &str) -> Result<SqlitePool> {
     Ok(pool)
 }
 
-pub async fn insert_burn(
-    db: &SqlitePool,
-    tx_hash_b64: &str,
-    lt: i64,
-    utime: i64,
-    jetton_master_raw: &str,
-    owner_raw: &str,
-    jetton_wallet_raw: &str,
-    amount_raw: &str,
-    custom_payload: Option<&[u8]>,
-) -> Result<u64> {
+/// `CREATE TABLE IF NOT EXISTS` does not add columns to existing databases.
+async fn ensure_column(pool: &SqlitePool, table: &str, column: &str, decl: &str) -> Result<()> {
+    let cols: Vec<(String,)> = sqlx::query_as(&format!("SELECT name FROM pragma_table_info('{table}')"))
+        .fetch_all(pool)
+        .await?;
+    if !cols.iter().any(|(name,)| name == column) {
+        sqlx::query(&format!("ALTER TABLE {table} ADD COLUMN {column} {decl}"))
+            .execute(pool)
+            .await?;
+    }
+    Ok(())
+}
+
+#[tracing::instrument(skip_all, fields(ton_tx = b.tx_hash_b64))]
+pub async fn insert_burn<'e>(db: impl Executor<'e, Database = Sqlite>, b: &NewBurn<'_>) -> Result<u64> {
     let res = sqlx::query!(
         r#"INSERT OR IGNORE INTO burns
            (tx_hash_b64, lt, utime, jetton_master_raw, owner_raw, jetton_wallet_raw, amount_raw, custom_payload)
            VALUES (?, ?, ?, ?, ?, ?, ?, ?)"#,
-        tx_hash_b64, lt, utime, jetton_master_raw, owner_raw, jetton_wallet_raw, amount_raw, custom_payload
+        b.tx_hash_b64, b.lt, b.utime, b.jetton_master_raw, b.owner_raw, b.jetton_wallet_raw, b.amount_raw,
+        b.custom_payload
     )
     .execute(db)
     .await?;
     Ok(res.rows_affected())
 }
 
-pub async fn list_burns(db: &SqlitePool, limit: i64) -> Result<Vec<BurnRow>> {
+/// Store a new burn together with its signed attestation, or nothing: a burn
+/// is never left without one. `None` if the burn was already stored.
+pub async fn insert_attested_burn(db: &SqlitePool, b: &NewBurn<'_>, a: &NewAttestation<'_>) -> Result<Option<i64>> {
+    let mut tx = db.begin().await?;
+    if insert_burn(&mut *tx, b).await? == 0 {
+        return Ok(None);
+    }
+    let id = insert_attestation_in(&mut tx, a).await?;
+    tx.commit().await?;
+    Ok(Some(id))
+}
+
+pub async fn list_burns(db: &SqlitePool, f: &BurnFilter, page: Page) -> Result<Vec<BurnRow>> {
+    let mut q = QueryBuilder::<Sqlite>::new(
+        "SELECT id, tx_hash_b64, lt, utime, jetton_master_raw, owner_raw, jetton_wallet_raw, amount_raw, created_at
+         FROM burns WHERE 1=1",
+    );
+    if let Some(owner) = &f.owner {
+        q.push(" AND owner_raw = ").push_bind(owner).push(" COLLATE NOCASE");
+    }
+    if let Some(master) = &f.jetton_master {
+        q.push(" AND jetton_master_raw = ").push_bind(master).push(" COLLATE NOCASE");
+    }
+    if let Some(from) = f.from_ts {
+        q.push(" AND utime >= ").push_bind(from);
+    }
+    if let Some(to) = f.to_ts {
+        q.push(" AND utime < ").push_bind(to);
+    }
+    push_amount_range(&mut q, "amount_raw", f.min_amount, f.max_amount);
+    fetch_page(db, q, "id", page).await
+}
+
+/// Insert an attestation with the relayer's signature in one transaction.
+pub async fn insert_attestation(db: &SqlitePool, a: &NewAttestation<'_>) -> Result<i64> {
+    let mut tx = db.begin().await?;
+    let id = insert_attestation_in(&mut tx, a).await?;
+    tx.commit().await?;
+    Ok(id)
+}
+
+#[tracing::instrument(skip_all, fields(att_hash = a.payload_hash_hex))]
+async fn insert_attestation_in(conn: &mut SqliteConnection, a: &NewAttestation<'_>) -> Result<i64> {
+    let id = sqlx::query!(
+        r#"INSERT INTO attestations(kind, payload_borsh, payload_hash_hex, src_tx_hash_b64, amount_dst, dust_raw,
+                                   status, created_at)
+           VALUES (?, ?, ?, ?, ?, ?, 'pending', strftime('%s','now'))"#,
+        a.kind, a.payload_borsh, a.payload_hash_hex, a.src_tx_hash_b64, a.amount_dst, a.dust_raw
+    )
+    .execute(&mut *conn)
+    .await?
+    .last_insert_rowid();
+    insert_signature(&mut *conn, id, a.signer_hex, a.signature_hex).await?;
+    Ok(id)
+}
+
+/// Owner and jetton master filters go through the burn each attestation was built from.
+pub async fn list_attestations(db: &SqlitePool, f: &AttFilter, page: Page) -> Result<Vec<AttRow>> {
+    let mut q = QueryBuilder::<Sqlite>::new(
+        "SELECT a.id, a.kind, a.payload_borsh, a.payload_hash_hex, a.src_tx_hash_b64, a.amount_dst, a.dust_raw,
+                a.status, a.attempts, a.last_error, a.created_at
+         FROM attestations a",
+    );
+    if f.owner.is_some() || f.jetton_master.is_some() {
+        q.push(" JOIN burns b ON b.tx_hash_b64 = a.src_tx_hash_b64");
+    }
+    q.push(" WHERE 1=1");
+    if let Some(kind) = &f.kind {
+        q.push(" AND a.kind = ").push_bind(kind);
+    }
+    if let Some(status) = &f.status {
+        q.push(" AND a.status = ").push_bind(status);
+    }
+    if let Some(src) = &f.src_tx_hash_b64 {
+        q.push(" AND a.src_tx_hash_b64 = ").push_bind(src);
+    }
+    if let Some(owner) = &f.owner {
+        q.push(" AND b.owner_raw = ").push_bind(owner).push(" COLLATE NOCASE");
+    }
+    if let Some(master) = &f.jetton_master {
+        q.push(" AND b.jetton_master_raw = ").push_bind(master).push(" COLLATE NOCASE");
+    }
+    if let Some(from) = f.from_ts {
+        q.push(" AND a.created_at >= ").push_bind(from);
+    }
+    if let Some(to) = f.to_ts {
+        q.push(" AND a.created_at < ").push_bind(to);
+    }
+    push_amount_range(&mut q, "a.amount_dst", f.min_amount, f.max_amount);
+    fetch_page(db, q, "a.id", page).await
+}
+
+/// Amounts are stored as canonical decimal strings (no sign, no leading
+/// zeros), so comparing by (length, text) orders them numerically without
+/// the i64 overflow of a CAST.
+fn push_amount_range(q: &mut QueryBuilder<'_, Sqlite>, col: &str, min: Option<u128>, max: Option<u128>) {
+    if let Some(min) = min {
+        let m = min.to_string();
+        q.push(format!(" AND (length({col}) > ")).push_bind(m.len() as i64);
+        q.push(format!(" OR (length({col}) = ")).push_bind(m.len() as i64);
+        q.push(format!(" AND {col} >= ")).push_bind(m).push("))");
+    }
+    if let Some(max) = max {
+        let m = max.to_string();
+        q.push(format!(" AND (length({col}) < ")).push_bind(m.len() as i64);
+        q.push(format!(" OR (length({col}) = ")).push_bind(m.len() as i64);
+        q.push(format!(" AND {col} <= ")).push_bind(m).push("))");
+    }
+}
+
+/// Apply the keyset bounds and run the query. Pages are always returned
+/// newest first; `after_id` walks forward by reading ascending and reversing.
+async fn fetch_page<T>(db: &SqlitePool, mut q: QueryBuilder<'_, Sqlite>, id_col: &str, page: Page) -> Result<Vec<T>>
+where
+    T: for<'r> sqlx::FromRow<'r, sqlx::sqlite::SqliteRow> + Send + Unpin,
+{
+    if let Some(before) = page.before_id {
+        q.push(format!(" AND {id_col} < ")).push_bind(before);
+    }
+    let forward = page.after_id.is_some();
+    if let Some(after) = page.after_id {
+        q.push(format!(" AND {id_col} > ")).push_bind(after);
+    }
+    q.push(format!(" ORDER BY {id_col} {} LIMIT ", if forward { "ASC" } else { "DESC" }));
+    q.push_bind(page.limit);
+
+    let mut rows = q.build_query_as::<T>().fetch_all(db).await?;
+    if forward {
+        rows.reverse();
+    }
+    Ok(rows)
+}
+
+/// Oldest pending attestations, leaving out burns of the `skip_masters`
+/// jettons (raw addresses) so paused routes do not hold up the rest.
+pub async fn list_pending_attestations(db: &SqlitePool, skip_masters: &[String], limit: i64) -> Result<Vec<PendingAtt>> {
+    let skip = serde_json::to_string(skip_masters).expect("strings serialize");
     let rows = sqlx::query_as!(
-        BurnRow,
-        r#"SELECT id, tx_hash_b64, lt, utime, jetton_master_raw,
-                  owner_raw, jetton_wallet_raw, amount_raw, created_at
-           FROM burns ORDER BY id DESC LIMIT ?"#,
+        PendingAtt,
+        r#"SELECT a.id AS "id!", a.payload_borsh AS "payload_borsh!", a.payload_hash_hex AS "payload_hash_hex!",
+                  a.src_tx_hash_b64, a.attempts, a.sol_signature, b.owner_raw AS "owner_raw?"
+           FROM attestations a LEFT JOIN burns b ON b.tx_hash_b64 = a.src_tx_hash_b64
+           WHERE a.status = 'pending'
+             AND (b.jetton_master_raw IS NULL OR b.jetton_master_raw NOT IN (SELECT value FROM json_each(?)))
+           ORDER BY a.id ASC LIMIT ?"#,
+        skip, limit
+    )
+    .fetch_all(db)
+    .await?;
+    Ok(rows)
+}
+
+/// Oldest-first batch of submitted attestations whose transaction is not
+/// finalized yet.
+pub async fn list_unconfirmed_attestations(db: &SqlitePool, limit: i64) -> Result<Vec<PendingAtt>> {
+    let rows = sqlx::query_as!(
+        PendingAtt,
+        r#"SELECT a.id AS "id!", a.payload_borsh AS "payload_borsh!", a.payload_hash_hex AS "payload_hash_hex!",
+                  a.src_tx_hash_b64, a.attempts, a.sol_signature, b.owner_raw AS "owner_raw?"
+           FROM attestations a LEFT JOIN burns b ON b.tx_hash_b64 = a.src_tx_hash_b64
+           WHERE a.status = 'submitted' AND a.sol_signature IS NOT NULL AND a.confirmed_at IS NULL
+           ORDER BY a.id ASC LIMIT ?"#,
         limit
     )
     .fetch_all(db)
@@ -75,35 +404,523 @@ pub async fn list_burns(db: &SqlitePool, limit: i64) -> Result<Vec<BurnRow>> {
     Ok(rows)
 }
 
-pub async fn insert_attestation(
+/// Record that a submitted attestation's transaction was finalized in `slot`.
+/// Returns false if it was not awaiting confirmation.
+pub async fn confirm_attestation(db: &SqlitePool, id: i64, slot: i64) -> Result<bool> {
+    let res = sqlx::query!(
+        r#"UPDATE attestations SET confirmed_at = strftime('%s','now'), confirmed_slot = ?,
+               updated_at = strftime('%s','now')
+           WHERE id = ? AND status = 'submitted' AND confirmed_at IS NULL"#,
+        slot, id
+    )
+    .execute(db)
+    .await?;
+    Ok(res.rows_affected() == 1)
+}
+
+/// Mark a submitted attestation `failed` after its transaction failed on
+/// chain. The latest `submissions` row is updated rather than a new attempt
+/// added. Returns false if it was not awaiting confirmation.
+pub async fn fail_submitted_attestation(db: &SqlitePool, id: i64, error: &str) -> Result<bool> {
+    let mut tx = db.begin().await?;
+    let n = sqlx::query!(
+        r#"UPDATE attestations SET status = 'failed', last_error = ?, updated_at = strftime('%s','now')
+           WHERE id = ? AND status = 'submitted' AND confirmed_at IS NULL"#,
+        error, id
+    )
+    .execute(&mut *tx)
+    .await?
+    .rows_affected();
+    if n == 0 {
+        return Ok(false);
+    }
+    sqlx::query!(
+        r#"UPDATE submissions SET status = 'failed', error = ?
+           WHERE id = (SELECT MAX(id) FROM submissions WHERE attestation_id = ?)"#,
+        error, id
+    )
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await?;
+    Ok(true)
+}
+
+pub async fn count_pending_attestations(db: &SqlitePool) -> Result<i64> {
+    let n = sqlx::query_scalar!(r#"SELECT COUNT(*) AS "n!: i64" FROM attestations WHERE status = 'pending'"#)
+        .fetch_one(db)
+        .await?;
+    Ok(n)
+}
+
+/// Record the outcome of one submission attempt, both as the attestation's
+/// current state and in the `submissions` history. History attempts are
+/// numbered across requeues; `attestations.attempts` counts since the last one.
+#[tracing::instrument(skip(db, error))]
+pub async fn record_submission(
     db: &SqlitePool,
-    kind: &str,
-    payload_borsh: &[u8],
-    payload_hash_hex: &str,
-    src_tx_hash_b64: Option<&str>,
-) -> Result<i64> {
+    id: i64,
+    status: &str,
+    error: Option<&str>,
+    sol_signature: Option<&str>,
+) -> Result<()> {
+    let mut tx = db.begin().await?;
+    sqlx::query!(
+        r#"UPDATE attestations
+           SET status = ?, attempts = attempts + 1, last_error = ?,
+               sol_signature = COALESCE(?, sol_signature), updated_at = strftime('%s','now')
+           WHERE id = ?"#,
+        status, error, sol_signature, id
+    )
+    .execute(&mut *tx)
+    .await?;
+    let attempt = sqlx::query_scalar!(
+        r#"SELECT COALESCE(MAX(attempt), 0) + 1 AS "n!: i64" FROM submissions WHERE attestation_id = ?"#,
+        id
+    )
+    .fetch_one(&mut *tx)
+    .await?;
+    sqlx::query!(
+        r#"INSERT INTO submissions(attestation_id, attempt, status, error, sol_signature) VALUES (?, ?, ?, ?, ?)"#,
+        id, attempt, status, error, sol_signature
+    )
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Put an attestation back in the submission queue with fresh attempts.
+/// Only applies while its status is still `from` (`None` for rows from before
+/// the queue existed); returns whether it did.
+pub async fn requeue_attestation(db: &SqlitePool, id: i64, from: Option<&str>) -> Result<bool> {
     let res = sqlx::query!(
-        r#"INSERT INTO attestations(kind, payload_borsh, payload_hash_hex, src_tx_hash_b64, created_at)
-           VALUES (?, ?, ?, ?, strftime('%s','now'))"#,
-        kind, payload_borsh, payload_hash_hex, src_tx_hash_b64
+        r#"UPDATE attestations SET status = 'pending', attempts = 0, last_error = NULL,
+               updated_at = strftime('%s','now')
+           WHERE id = ? AND status IS ?"#,
+        id, from
     )
     .execute(db)
     .await?;
-    Ok(res.last_insert_rowid())
+    Ok(res.rows_affected() == 1)
 }
 
-pub async fn list_attestations(db: &SqlitePool, limit: i64) -> Result<Vec<AttRow>> {
+/// Retire an attestation replaced by a rebuild, if its status is still `from`.
+pub async fn supersede_attestation(db: &SqlitePool, id: i64, from: Option<&str>) -> Result<bool> {
+    let res = sqlx::query!(
+        r#"UPDATE attestations SET status = 'superseded', updated_at = strftime('%s','now')
+           WHERE id = ? AND status IS ?"#,
+        id, from
+    )
+    .execute(db)
+    .await?;
+    Ok(res.rows_affected() == 1)
+}
+
+pub async fn list_submissions(db: &SqlitePool, attestation_id: i64) -> Result<Vec<SubmissionRow>> {
     let rows = sqlx::query_as!(
-        AttRow,
-        r#"SELECT id, kind, payload_hash_hex, src_tx_hash_b64, created_at
-           FROM attestations ORDER BY id DESC LIMIT ?"#,
-        limit
+        SubmissionRow,
+        r#"SELECT attempt, status, error, sol_signature, created_at
+           FROM submissions WHERE attestation_id = ? ORDER BY id ASC"#,
+        attestation_id
+    )
+    .fetch_all(db)
+    .await?;
+    Ok(rows)
+}
+
+/// Returns 0 if this signer already signed the attestation.
+pub async fn insert_signature<'e>(
+    db: impl Executor<'e, Database = Sqlite>,
+    attestation_id: i64,
+    signer_hex: &str,
+    signature_hex: &str,
+) -> Result<u64> {
+    let res = sqlx::query!(
+        r#"INSERT OR IGNORE INTO signatures(attestation_id, signer_hex, signature_hex) VALUES (?, ?, ?)"#,
+        attestation_id, signer_hex, signature_hex
+    )
+    .execute(db)
+    .await?;
+    Ok(res.rows_affected())
+}
+
+pub async fn list_signatures(db: &SqlitePool, attestation_id: i64) -> Result<Vec<SignatureRow>> {
+    let rows = sqlx::query_as!(
+        SignatureRow,
+        r#"SELECT signer_hex, signature_hex, created_at FROM signatures WHERE attestation_id = ? ORDER BY signer_hex"#,
+        attestation_id
+    )
+    .fetch_all(db)
+    .await?;
+    Ok(rows)
+}
+
+pub async fn get_burn(db: &SqlitePool, tx_hash_b64: &str) -> Result<Option<BurnDetail>> {
+    let row = sqlx::query_as!(
+        BurnDetail,
+        r#"SELECT id AS "id!", tx_hash_b64 AS "tx_hash_b64!", lt AS "lt!", utime AS "utime!",
+                  jetton_master_raw AS "jetton_master_raw!", owner_raw AS "owner_raw!",
+                  jetton_wallet_raw AS "jetton_wallet_raw!", amount_raw AS "amount_raw!",
+                  custom_payload, created_at AS "created_at!"
+           FROM burns WHERE tx_hash_b64 = ?"#,
+        tx_hash_b64
+    )
+    .fetch_optional(db)
+    .await?;
+    Ok(row)
+}
+
+pub async fn get_attestation_by_hash(db: &SqlitePool, hash_hex: &str) -> Result<Option<AttDetail>> {
+    let row = sqlx::query_as!(
+        AttDetail,
+        r#"SELECT id AS "id!", kind AS "kind!", payload_borsh AS "payload_borsh!",
+                  payload_hash_hex AS "payload_hash_hex!", src_tx_hash_b64, amount_dst, dust_raw,
+                  status, attempts, last_error, sol_signature, confirmed_at, confirmed_slot,
+                  updated_at, created_at AS "created_at!"
+           FROM attestations WHERE payload_hash_hex = ?"#,
+        hash_hex
+    )
+    .fetch_optional(db)
+    .await?;
+    Ok(row)
+}
+
+/// Latest attestation built from a TON transaction.
+pub async fn get_attestation_by_src_tx(db: &SqlitePool, src_tx_hash_b64: &str) -> Result<Option<AttDetail>> {
+    let row = sqlx::query_as!(
+        AttDetail,
+        r#"SELECT id AS "id!", kind AS "kind!", payload_borsh AS "payload_borsh!",
+                  payload_hash_hex AS "payload_hash_hex!", src_tx_hash_b64, amount_dst, dust_raw,
+                  status, attempts, last_error, sol_signature, confirmed_at, confirmed_slot,
+                  updated_at, created_at AS "created_at!"
+           FROM attestations WHERE src_tx_hash_b64 = ? ORDER BY id DESC LIMIT 1"#,
+        src_tx_hash_b64
+    )
+    .fetch_optional(db)
+    .await?;
+    Ok(row)
+}
+
+/// Returns 0 if an entry with the same key is already quarantined.
+#[tracing::instrument(skip_all, fields(qkey = q.qkey, reason_code = q.reason_code))]
+pub async fn insert_quarantine(db: &SqlitePool, q: &NewQuarantine<'_>) -> Result<u64> {
+    let res = sqlx::query!(
+        r#"INSERT OR IGNORE INTO quarantine
+           (qkey, tx_hash_b64, jetton_master_raw, reason_code, reason, releasable, raw_json)
+           VALUES (?, ?, ?, ?, ?, ?, ?)"#,
+        q.qkey, q.tx_hash_b64, q.jetton_master_raw, q.reason_code, q.reason, q.releasable, q.raw_json
+    )
+    .execute(db)
+    .await?;
+    Ok(res.rows_affected())
+}
+
+pub async fn list_quarantine(db: &SqlitePool, status: Option<&str>, limit: i64) -> Result<Vec<QuarantineRow>> {
+    let rows = sqlx::query_as!(
+        QuarantineRow,
+        r#"SELECT id AS "id!", qkey, tx_hash_b64, jetton_master_raw, reason_code, reason,
+                  releasable AS "releasable: bool", status, raw_json, created_at, updated_at
+           FROM quarantine WHERE (?1 IS NULL OR status = ?1) ORDER BY id DESC LIMIT ?2"#,
+        status, limit
+    )
+    .fetch_all(db)
+    .await?;
+    Ok(rows)
+}
+
+/// Most recent quarantine entry for a TON transaction.
+pub async fn get_quarantine_by_tx(db: &SqlitePool, tx_hash_b64: &str) -> Result<Option<QuarantineRow>> {
+    let row = sqlx::query_as!(
+        QuarantineRow,
+        r#"SELECT id AS "id!", qkey, tx_hash_b64, jetton_master_raw, reason_code, reason,
+                  releasable AS "releasable: bool", status, raw_json, created_at, updated_at
+           FROM quarantine WHERE tx_hash_b64 = ? ORDER BY id DESC LIMIT 1"#,
+        tx_hash_b64
+    )
+    .fetch_optional(db)
+    .await?;
+    Ok(row)
+}
+
+pub async fn get_quarantine(db: &SqlitePool, id: i64) -> Result<Option<QuarantineRow>> {
+    let row = sqlx::query_as!(
+        QuarantineRow,
+        r#"SELECT id AS "id!", qkey, tx_hash_b64, jetton_master_raw, reason_code, reason,
+                  releasable AS "releasable: bool", status, raw_json, created_at, updated_at
+           FROM quarantine WHERE id = ?"#,
+        id
+    )
+    .fetch_optional(db)
+    .await?;
+    Ok(row)
+}
+
+/// Update status (and optionally the reason) of a quarantine entry.
+pub async fn set_quarantine_status(db: &SqlitePool, id: i64, status: &str, reason: Option<&str>) -> Result<()> {
+    sqlx::query!(
+        r#"UPDATE quarantine SET status = ?, reason = COALESCE(?, reason), updated_at = strftime('%s','now')
+           WHERE id = ?"#,
+        status, reason, id
+    )
+    .execute(db)
+    .await?;
+    Ok(())
+}
+
+pub async fn insert_event(db: &SqlitePool, name: &str, data: &str) -> Result<i64> {
+    let id = sqlx::query_scalar!(
+        r#"INSERT INTO events(name, data) VALUES (?, ?) RETURNING id AS "id!: i64""#,
+        name, data
+    )
+    .fetch_one(db)
+    .await?;
+    Ok(id)
+}
+
+/// Events after `after_id`, oldest first.
+pub async fn list_events_after(db: &SqlitePool, after_id: i64, limit: i64) -> Result<Vec<EventRow>> {
+    let rows = sqlx::query_as!(
+        EventRow,
+        r#"SELECT id, name, data FROM events WHERE id > ? ORDER BY id ASC LIMIT ?"#,
+        after_id, limit
+    )
+    .fetch_all(db)
+    .await?;
+    Ok(rows)
+}
+
+pub async fn latest_event_id(db: &SqlitePool) -> Result<Option<i64>> {
+    let id = sqlx::query_scalar!(r#"SELECT MAX(id) AS "id: i64" FROM events"#)
+        .fetch_one(db)
+        .await?;
+    Ok(id)
+}
+
+pub async fn oldest_event_id(db: &SqlitePool) -> Result<Option<i64>> {
+    let id = sqlx::query_scalar!(r#"SELECT MIN(id) AS "id: i64" FROM events"#)
+        .fetch_one(db)
+        .await?;
+    Ok(id)
+}
+
+pub async fn delete_events_before(db: &SqlitePool, created_before: i64) -> Result<u64> {
+    let res = sqlx::query!("DELETE FROM events WHERE created_at < ?", created_before)
+        .execute(db)
+        .await?;
+    Ok(res.rows_affected())
+}
+
+pub async fn insert_webhook(db: &SqlitePool, url: &str, secret: &str, types: &str) -> Result<i64> {
+    let id = sqlx::query_scalar!(
+        r#"INSERT INTO webhooks(url, secret, types) VALUES (?, ?, ?) RETURNING id AS "id!: i64""#,
+        url, secret, types
+    )
+    .fetch_one(db)
+    .await?;
+    Ok(id)
+}
+
+pub async fn list_webhooks(db: &SqlitePool) -> Result<Vec<WebhookRow>> {
+    let rows = sqlx::query_as!(
+        WebhookRow,
+        r#"SELECT id AS "id!", url, types, enabled AS "enabled: bool", created_at
+           FROM webhooks ORDER BY id ASC"#
+    )
+    .fetch_all(db)
+    .await?;
+    Ok(rows)
+}
+
+/// Removes the webhook and its outstanding deliveries; dead letters are kept.
+pub async fn delete_webhook(db: &SqlitePool, id: i64) -> Result<u64> {
+    let mut tx = db.begin().await?;
+    sqlx::query!("DELETE FROM webhook_deliveries WHERE webhook_id = ?", id).execute(&mut *tx).await?;
+    let n = sqlx::query!("DELETE FROM webhooks WHERE id = ?", id).execute(&mut *tx).await?.rows_affected();
+    tx.commit().await?;
+    Ok(n)
+}
+
+/// Queue deliveries and move the fan-out cursor in one transaction, so each
+/// event is queued for each webhook exactly once.
+pub async fn enqueue_webhook_deliveries(
+    db: &SqlitePool,
+    deliveries: &[NewDelivery],
+    cursor_key: &str,
+    last_event_id: i64,
+) -> Result<()> {
+    let mut tx = db.begin().await?;
+    for d in deliveries {
+        sqlx::query!(
+            "INSERT INTO webhook_deliveries(webhook_id, event_id, event_name, payload) VALUES (?, ?, ?, ?)",
+            d.webhook_id, d.event_id, d.event_name, d.payload
+        )
+        .execute(&mut *tx)
+        .await?;
+    }
+    let v = last_event_id.to_string();
+    sqlx::query!(
+        r#"INSERT INTO cursors(k, v) VALUES(?, ?) ON CONFLICT(k) DO UPDATE SET v=excluded.v"#,
+        cursor_key, v
+    )
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Deliveries whose next attempt is due, for enabled webhooks, oldest first.
+pub async fn list_due_deliveries(db: &SqlitePool, now: i64, limit: i64) -> Result<Vec<DueDelivery>> {
+    let rows = sqlx::query_as!(
+        DueDelivery,
+        r#"SELECT d.id AS "id!", d.webhook_id, w.url, w.secret, d.event_id, d.event_name, d.payload, d.attempts
+           FROM webhook_deliveries d JOIN webhooks w ON w.id = d.webhook_id
+           WHERE d.next_attempt_at <= ? AND w.enabled = 1
+           ORDER BY d.id ASC LIMIT ?"#,
+        now, limit
+    )
+    .fetch_all(db)
+    .await?;
+    Ok(rows)
+}
+
+pub async fn delete_delivery(db: &SqlitePool, id: i64) -> Result<()> {
+    sqlx::query!("DELETE FROM webhook_deliveries WHERE id = ?", id).execute(db).await?;
+    Ok(())
+}
+
+pub async fn retry_delivery(
+    db: &SqlitePool,
+    id: i64,
+    next_attempt_at: i64,
+    error: &str,
+    status: Option<i64>,
+) -> Result<()> {
+    sqlx::query!(
+        r#"UPDATE webhook_deliveries
+           SET attempts = attempts + 1, next_attempt_at = ?, last_error = ?, last_status = ?
+           WHERE id = ?"#,
+        next_attempt_at, error, status, id
+    )
+    .execute(db)
+    .await?;
+    Ok(())
+}
+
+/// Move a delivery that used up its attempts to `webhook_dead_letters`.
+pub async fn dead_letter_delivery(db: &SqlitePool, id: i64, error: &str, status: Option<i64>) -> Result<()> {
+    let mut tx = db.begin().await?;
+    sqlx::query!(
+        r#"INSERT INTO webhook_dead_letters(webhook_id, event_id, event_name, payload, attempts, last_error, last_status, created_at)
+           SELECT webhook_id, event_id, event_name, payload, attempts + 1, ?, ?, created_at
+           FROM webhook_deliveries WHERE id = ?"#,
+        error, status, id
+    )
+    .execute(&mut *tx)
+    .await?;
+    sqlx::query!("DELETE FROM webhook_deliveries WHERE id = ?", id).execute(&mut *tx).await?;
+    tx.commit().await?;
+    Ok(())
+}
+
+pub async fn list_dead_letters(db: &SqlitePool, webhook_id: Option<i64>, limit: i64) -> Result<Vec<DeadLetterRow>> {
+    let rows = sqlx::query_as!(
+        DeadLetterRow,
+        r#"SELECT id AS "id!", webhook_id, event_id, event_name, payload, attempts, last_error, last_status,
+                  created_at, failed_at
+           FROM webhook_dead_letters WHERE (?1 IS NULL OR webhook_id = ?1) ORDER BY id DESC LIMIT ?2"#,
+        webhook_id, limit
+    )
+    .fetch_all(db)
+    .await?;
+    Ok(rows)
+}
+
+/// Put a dead letter back in the delivery queue with fresh attempts.
+/// Returns the new delivery id, or `None` if there is no such dead letter.
+pub async fn replay_dead_letter(db: &SqlitePool, id: i64) -> Result<Option<i64>> {
+    let mut tx = db.begin().await?;
+    let delivery = sqlx::query_scalar!(
+        r#"INSERT INTO webhook_deliveries(webhook_id, event_id, event_name, payload)
+           SELECT webhook_id, event_id, event_name, payload FROM webhook_dead_letters
+           WHERE id = ? AND webhook_id IN (SELECT id FROM webhooks)
+           RETURNING id AS "id!: i64""#,
+        id
+    )
+    .fetch_optional(&mut *tx)
+    .await?;
+    if delivery.is_some() {
+        sqlx::query!("DELETE FROM webhook_dead_letters WHERE id = ?", id).execute(&mut *tx).await?;
+    }
+    tx.commit().await?;
+    Ok(delivery)
+}
+
+pub async fn insert_audit(db: &SqlitePool, e: &AuditEntry) -> Result<()> {
+    sqlx::query!(
+        "INSERT INTO admin_audit(token_name, scope, method, path, status, remote_addr) VALUES (?, ?, ?, ?, ?, ?)",
+        e.token_name, e.scope, e.method, e.path, e.status, e.remote_addr
+    )
+    .execute(db)
+    .await?;
+    Ok(())
+}
+
+/// Audit rows newest first, older than `before_id` when given.
+pub async fn list_audit(db: &SqlitePool, before_id: Option<i64>, limit: i64) -> Result<Vec<AuditRow>> {
+    let rows = sqlx::query_as!(
+        AuditRow,
+        r#"SELECT id AS "id!", at, token_name, scope, method, path, status, remote_addr
+           FROM admin_audit WHERE (?1 IS NULL OR id < ?1) ORDER BY id DESC LIMIT ?2"#,
+        before_id, limit
+    )
+    .fetch_all(db)
+    .await?;
+    Ok(rows)
+}
+
+/// Pause or resume `target` (`*` or a route name).
+pub async fn set_pause(db: &SqlitePool, target: &str, paused: bool, reason: Option<&str>, by: &str) -> Result<()> {
+    sqlx::query!(
+        r#"INSERT INTO pauses(target, paused, reason, changed_by) VALUES(?, ?, ?, ?)
+           ON CONFLICT(target) DO UPDATE SET paused=excluded.paused, reason=excluded.reason,
+               changed_by=excluded.changed_by, changed_at=strftime('%s','now'), version=version+1"#,
+        target, paused, reason, by
+    )
+    .execute(db)
+    .await?;
+    Ok(())
+}
+
+pub async fn list_pauses(db: &SqlitePool) -> Result<Vec<PauseRow>> {
+    let rows = sqlx::query_as!(
+        PauseRow,
+        r#"SELECT target AS "target!", paused AS "paused: bool", reason, changed_by, changed_at, version
+           FROM pauses ORDER BY target"#
+    )
+    .fetch_all(db)
+    .await?;
+    Ok(rows)
+}
+
+/// Changes not yet published as events.
+pub async fn list_unannounced_pauses(db: &SqlitePool) -> Result<Vec<PauseRow>> {
+    let rows = sqlx::query_as!(
+        PauseRow,
+        r#"SELECT target AS "target!", paused AS "paused: bool", reason, changed_by, changed_at, version
+           FROM pauses WHERE announced < version ORDER BY changed_at, target"#
     )
     .fetch_all(db)
     .await?;
     Ok(rows)
 }
 
+pub async fn mark_pause_announced(db: &SqlitePool, target: &str, version: i64) -> Result<()> {
+    sqlx::query!("UPDATE pauses SET announced = MAX(announced, ?) WHERE target = ?", version, target)
+        .execute(db)
+        .await?;
+    Ok(())
+}
+
 pub async fn set_cursor(db: &SqlitePool, k: &str, v: &str) -> Result<()> {
     sqlx::query!(r#"INSERT INTO cursors(k, v) VALUES(?, ?)
                     ON CONFLICT(k) DO UPDATE SET v=excluded.v"#, k, v)
@@ -112,12 +929,47 @@ pub async fn set_cursor(db: &SqlitePool, k: &str, v: &str) -> Result<()> {
 }
 
 pub async fn get_cursor(db: &SqlitePool, k: &str) -> Result<Option<String>> {
-    let row = sqlx::query!(r#"SELECT v FROM cursors WHERE k=? "#, k)
+    let row = sqlx::query!(r#"SELECT v AS "v!" FROM cursors WHERE k=? "#, k)
         .fetch_optional(db).await?;
     Ok(row.map(|r| r.v))
 }
 
-#[derive(Debug, sqlx::FromRow, serde::Serialize)]
+/// Keyset position for list queries; at most one of `before_id`/`after_id`.
+#[derive(Debug, Clone, Copy)]
+pub struct Page {
+    pub before_id: Option<i64>,
+    pub after_id: Option<i64>,
+    pub limit: i64,
+}
+
+#[derive(Debug, Default)]
+pub struct BurnFilter {
+    pub owner: Option<String>,
+    pub jetton_master: Option<String>,
+    /// Inclusive/exclusive bounds on the TON transaction time.
+    pub from_ts: Option<i64>,
+    pub to_ts: Option<i64>,
+    /// Inclusive bounds in TON raw units.
+    pub min_amount: Option<u128>,
+    pub max_amount: Option<u128>,
+}
+
+#[derive(Debug, Default)]
+pub struct AttFilter {
+    pub kind: Option<String>,
+    pub status: Option<String>,
+    pub src_tx_hash_b64: Option<String>,
+    pub owner: Option<String>,
+    pub jetton_master: Option<String>,
+    /// Inclusive/exclusive bounds on `created_at`.
+    pub from_ts: Option<i64>,
+    pub to_ts: Option<i64>,
+    /// Inclusive bounds on `amount_dst` (SPL base units).
+    pub min_amount: Option<u128>,
+    pub max_amount: Option<u128>,
+}
+
+#[derive(Debug, sqlx::FromRow, serde::Serialize, utoipa::ToSchema)]
 pub struct BurnRow {
     pub id: i64,
     pub tx_hash_b64: String,
@@ -130,11 +982,595 @@ pub struct BurnRow {
     pub created_at: i64,
 }
 
-#[derive(Debug, sqlx::FromRow, serde::Serialize)]
+#[derive(Debug, sqlx::FromRow, serde::Serialize, utoipa::ToSchema)]
 pub struct AttRow {
     pub id: i64,
     pub kind: String,
+    /// Rendered by the API through `attestations::payload_json`.
+    #[serde(skip)]
+    pub payload_borsh: Vec<u8>,
+    pub payload_hash_hex: String,
+    pub src_tx_hash_b64: Option<String>,
+    /// Destination amount in SPL base units.
+    pub amount_dst: Option<String>,
+    /// TON raw units truncated by decimal conversion.
+    pub dust_raw: Option<String>,
+    /// `pending`, `submitted`, `failed` or `superseded`; NULL for rows predating the queue.
+    pub status: Option<String>,
+    pub attempts: i64,
+    pub last_error: Option<String>,
+    pub created_at: i64,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct BurnDetail {
+    pub id: i64,
+    pub tx_hash_b64: String,
+    pub lt: i64,
+    pub utime: i64,
+    pub jetton_master_raw: String,
+    pub owner_raw: String,
+    pub jetton_wallet_raw: String,
+    pub amount_raw: String,
+    pub custom_payload: Option<Vec<u8>>,
+    pub created_at: i64,
+}
+
+/// Full attestation record, including the Borsh payload.
+#[derive(Debug, sqlx::FromRow)]
+pub struct AttDetail {
+    pub id: i64,
+    pub kind: String,
+    pub payload_borsh: Vec<u8>,
+    pub payload_hash_hex: String,
+    pub src_tx_hash_b64: Option<String>,
+    pub amount_dst: Option<String>,
+    pub dust_raw: Option<String>,
+    pub status: Option<String>,
+    pub attempts: i64,
+    pub last_error: Option<String>,
+    /// Signature of the Solana transaction that carried the attestation.
+    pub sol_signature: Option<String>,
+    /// When and in which slot that transaction was finalized.
+    pub confirmed_at: Option<i64>,
+    pub confirmed_slot: Option<i64>,
+    pub updated_at: Option<i64>,
+    pub created_at: i64,
+}
+
+#[derive(Debug, sqlx::FromRow, serde::Serialize)]
+pub struct SignatureRow {
+    pub signer_hex: String,
+    pub signature_hex: String,
+    pub created_at: i64,
+}
+
+#[derive(Debug, sqlx::FromRow, serde::Serialize)]
+pub struct SubmissionRow {
+    pub attempt: i64,
+    pub status: String,
+    pub error: Option<String>,
+    pub sol_signature: Option<String>,
+    pub created_at: i64,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct PendingAtt {
+    pub id: i64,
+    pub payload_borsh: Vec<u8>,
     pub payload_hash_hex: String,
     pub src_tx_hash_b64: Option<String>,
+    pub attempts: i64,
+    /// Set once a transaction was sent.
+    pub sol_signature: Option<String>,
+    pub owner_raw: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct EventRow {
+    pub id: i64,
+    pub name: String,
+    /// Encoded [`crate::events::RelayerEvent`].
+    pub data: String,
+}
+
+#[derive(Debug)]
+pub struct AuditEntry {
+    pub token_name: Option<String>,
+    pub scope: Option<&'static str>,
+    pub method: String,
+    /// Path and query string.
+    pub path: String,
+    pub status: i64,
+    pub remote_addr: Option<String>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct AuditRow {
+    pub id: i64,
+    pub at: i64,
+    pub token_name: Option<String>,
+    pub scope: Option<String>,
+    pub method: String,
+    pub path: String,
+    pub status: i64,
+    pub remote_addr: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct PauseRow {
+    pub target: String,
+    pub paused: bool,
+    pub reason: Option<String>,
+    pub changed_by: String,
+    pub changed_at: i64,
+    pub version: i64,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct WebhookRow {
+    pub id: i64,
+    pub url: String,
+    pub types: String,
+    pub enabled: bool,
+    pub created_at: i64,
+}
+
+/// Addresses are stored in canonical raw form (`TonAddress::to_raw`), so
+/// they group and compare as plain strings.
+#[derive(Debug)]
+pub struct NewBurn<'a> {
+    pub tx_hash_b64: &'a str,
+    pub lt: i64,
+    pub utime: i64,
+    pub jetton_master_raw: &'a str,
+    pub owner_raw: &'a str,
+    pub jetton_wallet_raw: &'a str,
+    pub amount_raw: &'a str,
+    pub custom_payload: Option<&'a [u8]>,
+}
+
+#[derive(Debug)]
+pub struct NewAttestation<'a> {
+    pub kind: &'a str,
+    pub payload_borsh: &'a [u8],
+    pub payload_hash_hex: &'a str,
+    pub src_tx_hash_b64: Option<&'a str>,
+    pub amount_dst: Option<&'a str>,
+    pub dust_raw: Option<&'a str>,
+    pub signer_hex: &'a str,
+    pub signature_hex: &'a str,
+}
+
+#[derive(Debug)]
+pub struct NewQuarantine<'a> {
+    pub qkey: &'a str,
+    pub tx_hash_b64: Option<&'a str>,
+    pub jetton_master_raw: &'a str,
+    pub reason_code: &'a str,
+    pub reason: &'a str,
+    pub releasable: bool,
+    pub raw_json: &'a str,
+}
+
+#[derive(Debug)]
+pub struct NewDelivery {
+    pub webhook_id: i64,
+    pub event_id: i64,
+    pub event_name: String,
+    pub payload: String,
+}
+
+#[derive(Debug)]
+pub struct DueDelivery {
+    pub id: i64,
+    pub webhook_id: i64,
+    pub url: String,
+    pub secret: String,
+    pub event_id: i64,
+    pub event_name: String,
+    pub payload: String,
+    pub attempts: i64,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct DeadLetterRow {
+    pub id: i64,
+    pub webhook_id: i64,
+    pub event_id: i64,
+    pub event_name: String,
+    pub payload: String,
+    pub attempts: i64,
+    pub last_error: Option<String>,
+    pub last_status: Option<i64>,
+    pub created_at: i64,
+    pub failed_at: i64,
+}
+
+#[derive(Debug, sqlx::FromRow, serde::Serialize)]
+pub struct QuarantineRow {
+    pub id: i64,
+    pub qkey: String,
+    pub tx_hash_b64: Option<String>,
+    pub jetton_master_raw: String,
+    pub reason_code: String,
+    pub reason: String,
+    pub releasable: bool,
+    /// `held`, `released` (awaiting the ingest worker), `processed` or `discarded`.
+    pub status: String,
+    pub raw_json: String,
     pub created_at: i64,
+    pub updated_at: i64,
+}
+
+// Aggregates behind `GET /stats`. Amounts are decimal TEXT up to u128 (39
+// digits), too wide for SQLite's i64 SUM, so each is left-padded to 40 digits
+// and summed as four 10-digit limbs. A limb sum overflows
+// only past ~9*10^8 rows; `AmountSum` carries between limbs when formatting,
+// so totals stay exact even beyond u128.
+
+/// Exact sum of a decimal amount column, most significant limb first; see
+/// the note above.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct AmountSum(pub [i64; 4]);
+
+impl std::ops::Add for AmountSum {
+    type Output = Self;
+
+    fn add(self, o: Self) -> Self {
+        let [a, b] = [self.0, o.0];
+        AmountSum([a[0] + b[0], a[1] + b[1], a[2] + b[2], a[3] + b[3]])
+    }
+}
+
+impl std::fmt::Display for AmountSum {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        const BASE: u128 = 10_000_000_000;
+        let mut limbs = self.0.map(|l| l as u128);
+        for i in (1..limbs.len()).rev() {
+            limbs[i - 1] += limbs[i] / BASE;
+            limbs[i] %= BASE;
+        }
+        let digits = format!("{}{:010}{:010}{:010}", limbs[0], limbs[1], limbs[2], limbs[3]);
+        match digits.trim_start_matches('0') {
+            "" => f.write_str("0"),
+            d => f.write_str(d),
+        }
+    }
+}
+
+/// Burns of one jetton master in one time bucket (`bucket` is its start).
+#[derive(Debug, Clone, PartialEq)]
+pub struct VolumeRow {
+    pub jetton_master_raw: String,
+    pub bucket: i64,
+    pub count: i64,
+    pub amount: AmountSum,
+}
+
+/// Burn volume per master and `bucket_secs`-aligned bucket since `since`.
+pub async fn burn_volume(db: &SqlitePool, bucket_secs: i64, since: i64) -> Result<Vec<VolumeRow>> {
+    let rows = sqlx::query!(
+        r#"WITH b AS (
+               SELECT jetton_master_raw, utime, substr('0000000000000000000000000000000000000000' || amount_raw, -40) AS a
+               FROM burns WHERE utime >= ?
+           )
+           SELECT jetton_master_raw AS "jetton_master_raw!", (utime / ?) * ? AS "bucket!: i64",
+                  COUNT(*) AS "count!: i64",
+                  SUM(CAST(substr(a, 1, 10) AS INTEGER)) AS "l0!: i64", SUM(CAST(substr(a, 11, 10) AS INTEGER)) AS "l1!: i64",
+                  SUM(CAST(substr(a, 21, 10) AS INTEGER)) AS "l2!: i64", SUM(CAST(substr(a, 31, 10) AS INTEGER)) AS "l3!: i64"
+           FROM b GROUP BY 1, 2 ORDER BY 1, 2"#,
+        since, bucket_secs, bucket_secs
+    )
+    .fetch_all(db)
+    .await?;
+    Ok(rows
+        .into_iter()
+        .map(|r| VolumeRow {
+            jetton_master_raw: r.jetton_master_raw,
+            bucket: r.bucket,
+            count: r.count,
+            amount: AmountSum([r.l0, r.l1, r.l2, r.l3]),
+        })
+        .collect())
+}
+
+/// All-time burn count and volume per master.
+pub async fn burn_totals(db: &SqlitePool) -> Result<Vec<(String, i64, AmountSum)>> {
+    let rows = sqlx::query!(
+        r#"WITH b AS (SELECT jetton_master_raw, substr('0000000000000000000000000000000000000000' || amount_raw, -40) AS a FROM burns)
+           SELECT jetton_master_raw AS "jetton_master_raw!", COUNT(*) AS "count!: i64",
+                  SUM(CAST(substr(a, 1, 10) AS INTEGER)) AS "l0!: i64", SUM(CAST(substr(a, 11, 10) AS INTEGER)) AS "l1!: i64",
+                  SUM(CAST(substr(a, 21, 10) AS INTEGER)) AS "l2!: i64", SUM(CAST(substr(a, 31, 10) AS INTEGER)) AS "l3!: i64"
+           FROM b GROUP BY 1 ORDER BY 1"#
+    )
+    .fetch_all(db)
+    .await?;
+    Ok(rows.into_iter().map(|r| (r.jetton_master_raw, r.count, AmountSum([r.l0, r.l1, r.l2, r.l3]))).collect())
+}
+
+/// All-time attestation count per status (`None` for legacy rows).
+pub async fn attestation_status_counts(db: &SqlitePool) -> Result<Vec<(Option<String>, i64)>> {
+    let rows = sqlx::query!(r#"SELECT status, COUNT(*) AS "count!: i64" FROM attestations GROUP BY status ORDER BY status"#)
+        .fetch_all(db)
+        .await?;
+    Ok(rows.into_iter().map(|r| (r.status, r.count)).collect())
+}
+
+/// Median seconds from burn (`utime`) to the finalization of the Solana
+/// transaction that delivered it (`confirmed_at`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct FinalizationRow {
+    pub median_secs: f64,
+    pub count: i64,
+}
+
+/// Finalization median for burns since `since` of the given masters, or of
+/// all of them with `None`. `None` when nothing was finalized; transfers
+/// only submitted so far do not count.
+pub async fn finalization_median(
+    db: &SqlitePool,
+    since: i64,
+    masters: Option<&[String]>,
+) -> Result<Option<FinalizationRow>> {
+    let masters = masters.map(|m| serde_json::to_string(m).expect("strings serialize"));
+    let r = sqlx::query!(
+        r#"WITH d AS (
+               SELECT a.confirmed_at - b.utime AS secs
+               FROM attestations a JOIN burns b ON b.tx_hash_b64 = a.src_tx_hash_b64
+               WHERE a.confirmed_at IS NOT NULL AND b.utime >= ?
+                 AND (? IS NULL OR b.jetton_master_raw IN (SELECT value FROM json_each(?)))
+           ), r AS (
+               SELECT secs, ROW_NUMBER() OVER (ORDER BY secs) AS rn, COUNT(*) OVER () AS n FROM d
+           )
+           SELECT AVG(secs) AS "median_secs: f64", MAX(n) AS "count: i64"
+           FROM r WHERE rn IN ((n + 1) / 2, (n + 2) / 2)"#,
+        since, masters, masters
+    )
+    .fetch_one(db)
+    .await?;
+    Ok(r.median_secs.zip(r.count).map(|(median_secs, count)| FinalizationRow { median_secs, count }))
+}
+
+/// Attestation outcomes per master for attestations created since `since`.
+/// Superseded attestations are left out; their replacement counts instead.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OutcomeRow {
+    pub jetton_master_raw: String,
+    pub attestations: i64,
+    pub submitted: i64,
+    pub failed: i64,
+    pub pending: i64,
+}
+
+pub async fn attestation_outcomes(db: &SqlitePool, since: i64) -> Result<Vec<OutcomeRow>> {
+    let rows = sqlx::query_as!(
+        OutcomeRow,
+        r#"SELECT b.jetton_master_raw AS "jetton_master_raw!", COUNT(*) AS "attestations!: i64",
+                  COUNT(*) FILTER (WHERE a.status = 'submitted') AS "submitted!: i64",
+                  COUNT(*) FILTER (WHERE a.status = 'failed') AS "failed!: i64",
+                  COUNT(*) FILTER (WHERE a.status = 'pending') AS "pending!: i64"
+           FROM attestations a JOIN burns b ON b.tx_hash_b64 = a.src_tx_hash_b64
+           WHERE a.created_at >= ? AND a.status IS NOT 'superseded'
+           GROUP BY 1 ORDER BY 1"#,
+        since
+    )
+    .fetch_all(db)
+    .await?;
+    Ok(rows)
+}
+
+/// Burns quarantined per master since `since`, whatever became of them.
+pub async fn quarantine_counts(db: &SqlitePool, since: i64) -> Result<Vec<(String, i64)>> {
+    let rows = sqlx::query!(
+        r#"SELECT jetton_master_raw, COUNT(*) AS "count!: i64" FROM quarantine
+           WHERE created_at >= ? GROUP BY 1 ORDER BY 1"#,
+        since
+    )
+    .fetch_all(db)
+    .await?;
+    Ok(rows.into_iter().map(|r| (r.jetton_master_raw, r.count)).collect())
+}
+
+/// Submission attempts since `since` and how many of them failed.
+pub async fn submission_attempts(db: &SqlitePool, since: i64) -> Result<(i64, i64)> {
+    let r = sqlx::query!(
+        r#"SELECT COUNT(*) AS "attempts!: i64", COUNT(*) FILTER (WHERE status = 'failed') AS "failed!: i64"
+           FROM submissions WHERE created_at >= ?"#,
+        since
+    )
+    .fetch_one(db)
+    .await?;
+    Ok((r.attempts, r.failed))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DestinationRow {
+    /// Destination Solana account, read from the attestation payload.
+    pub dst: [u8; 32],
+    pub count: i64,
+    /// Sum of `amount_dst`, in the route's SPL units.
+    pub amount: AmountSum,
+}
+
+/// The `limit` destinations receiving the most transfers from burns of
+/// `masters`, for attestations created since `since`. The account is sliced
+/// straight out of the Borsh payload, at [`crate::attestations::DST_OFFSET`]
+/// or, for V1 payloads, [`crate::attestations::DST_OFFSET_V1`].
+pub async fn top_destinations(db: &SqlitePool, masters: &[String], since: i64, limit: i64) -> Result<Vec<DestinationRow>> {
+    let (v2, v1) = (crate::attestations::DST_OFFSET as i64, crate::attestations::DST_OFFSET_V1 as i64);
+    let masters = serde_json::to_string(masters).expect("strings serialize");
+    let rows = sqlx::query!(
+        r#"WITH p AS (
+               SELECT a.payload_borsh AS payload, substr('0000000000000000000000000000000000000000' || COALESCE(a.amount_dst, '0'), -40) AS a,
+                      CASE substr(a.payload_borsh, 1, 1) WHEN x'02' THEN ? ELSE ? END AS off
+               FROM attestations a JOIN burns b ON b.tx_hash_b64 = a.src_tx_hash_b64
+               WHERE a.created_at >= ? AND a.status IS NOT 'superseded'
+                 AND b.jetton_master_raw IN (SELECT value FROM json_each(?))
+           )
+           SELECT substr(payload, off + 1, 32) AS "dst!: Vec<u8>", COUNT(*) AS "count!: i64",
+                  SUM(CAST(substr(a, 1, 10) AS INTEGER)) AS "l0!: i64", SUM(CAST(substr(a, 11, 10) AS INTEGER)) AS "l1!: i64",
+                  SUM(CAST(substr(a, 21, 10) AS INTEGER)) AS "l2!: i64", SUM(CAST(substr(a, 31, 10) AS INTEGER)) AS "l3!: i64"
+           FROM p WHERE length(payload) >= off + 32
+           GROUP BY 1 ORDER BY 2 DESC, SUM(CAST(a AS REAL)) DESC, 1 LIMIT ?"#,
+        v2, v1, since, masters, limit
+    )
+    .fetch_all(db)
+    .await?;
+    Ok(rows
+        .into_iter()
+        .filter_map(|r| {
+            let amount = AmountSum([r.l0, r.l1, r.l2, r.l3]);
+            Some(DestinationRow { dst: r.dst.try_into().ok()?, count: r.count, amount })
+        })
+        .collect())
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// Fresh file-backed database (an in-memory one is per connection).
+    pub(crate) async fn temp_db() -> SqlitePool {
+        let path = std::env::temp_dir().join(format!(
+            "relayer-test-{}-{}.db",
+            std::process::id(),
+            std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_nanos()
+        ));
+        init_db(&format!("sqlite://{}?mode=rwc", path.display())).await.unwrap()
+    }
+
+    pub(crate) fn burn<'a>(tx: &'a str, lt: i64, utime: i64, master: &'a str, owner: &'a str, amount: &'a str) -> NewBurn<'a> {
+        NewBurn {
+            tx_hash_b64: tx,
+            lt,
+            utime,
+            jetton_master_raw: master,
+            owner_raw: owner,
+            jetton_wallet_raw: "0:WW",
+            amount_raw: amount,
+            custom_payload: None,
+        }
+    }
+
+    /// A pending attestation signed by signer `aa`.
+    pub(crate) fn attestation<'a>(hash_hex: &'a str, src_tx: &'a str, payload: &'a [u8]) -> NewAttestation<'a> {
+        NewAttestation {
+            kind: "TON_TO_SOL",
+            payload_borsh: payload,
+            payload_hash_hex: hash_hex,
+            src_tx_hash_b64: Some(src_tx),
+            amount_dst: Some("5"),
+            dust_raw: None,
+            signer_hex: "aa",
+            signature_hex: "sig",
+        }
+    }
+
+    #[test]
+    fn amount_sums_carry_between_limbs() {
+        assert_eq!(AmountSum::default().to_string(), "0");
+        let max = AmountSum([340_282_366, 9_209_384_634, 6_337_460_743, 1_768_211_455]);
+        assert_eq!(max.to_string(), u128::MAX.to_string());
+        let twice = (max + max).to_string();
+        assert_eq!(twice, "680564733841876926926749214863536422910");
+        assert_eq!(AmountSum([0, 0, 0, 25_000_000_000]).to_string(), "25000000000");
+    }
+
+    #[tokio::test]
+    async fn burns_page_by_keyset_and_filter_by_amount() {
+        let db = temp_db().await;
+        let big = u128::MAX.to_string();
+        for (i, (owner, amount)) in [("0:AA", "5"), ("0:BB", "40"), ("0:AA", "300"), ("0:AA", big.as_str())]
+            .into_iter()
+            .enumerate()
+        {
+            let tx = format!("tx{i}");
+            insert_burn(&db, &burn(&tx, i as i64, 1_000 + i as i64, "0:MM", owner, amount)).await.unwrap();
+        }
+        let ids = |rows: Vec<BurnRow>| rows.into_iter().map(|r| r.id).collect::<Vec<_>>();
+        let page = |before_id, after_id| Page { before_id, after_id, limit: 2 };
+
+        let all = BurnFilter::default();
+        assert_eq!(ids(list_burns(&db, &all, page(None, None)).await.unwrap()), vec![4, 3]);
+        assert_eq!(ids(list_burns(&db, &all, page(Some(3), None)).await.unwrap()), vec![2, 1]);
+        assert_eq!(ids(list_burns(&db, &all, page(None, Some(1))).await.unwrap()), vec![3, 2]);
+
+        // "40" > "300" as text; the (length, text) comparison must not be fooled.
+        let f = BurnFilter { min_amount: Some(40), max_amount: Some(300), ..Default::default() };
+        assert_eq!(ids(list_burns(&db, &f, page(None, None)).await.unwrap()), vec![3, 2]);
+        let f = BurnFilter { min_amount: Some(u128::MAX), ..Default::default() };
+        assert_eq!(ids(list_burns(&db, &f, page(None, None)).await.unwrap()), vec![4]);
+
+        let f = BurnFilter { owner: Some("0:aa".into()), from_ts: Some(1_001), ..Default::default() };
+        assert_eq!(ids(list_burns(&db, &f, page(None, None)).await.unwrap()), vec![4, 3]);
+    }
+
+    #[tokio::test]
+    async fn submissions_are_kept_as_history() {
+        let db = temp_db().await;
+        let id = insert_attestation(&db, &attestation("ab", "tx0", b"x")).await.unwrap();
+        assert_eq!(insert_signature(&db, id, "aa", "sig").await.unwrap(), 0);
+
+        record_submission(&db, id, "pending", Some("rpc down"), None).await.unwrap();
+        record_submission(&db, id, "submitted", None, Some("5Sig")).await.unwrap();
+
+        let att = get_attestation_by_hash(&db, "ab").await.unwrap().unwrap();
+        assert_eq!((att.status.as_deref(), att.attempts, att.sol_signature.as_deref()), (Some("submitted"), 2, Some("5Sig")));
+        let subs = list_submissions(&db, id).await.unwrap();
+        assert_eq!(subs.iter().map(|s| (s.attempt, s.status.as_str())).collect::<Vec<_>>(), vec![(1, "pending"), (2, "submitted")]);
+        assert_eq!(subs[0].error.as_deref(), Some("rpc down"));
+        assert_eq!(get_attestation_by_src_tx(&db, "tx0").await.unwrap().unwrap().id, id);
+
+        assert_eq!(list_unconfirmed_attestations(&db, 10).await.unwrap().len(), 1);
+        assert!(confirm_attestation(&db, id, 77).await.unwrap());
+        assert!(!fail_submitted_attestation(&db, id, "late").await.unwrap());
+        assert!(list_unconfirmed_attestations(&db, 10).await.unwrap().is_empty());
+
+        let other = insert_attestation(&db, &attestation("cd", "tx1", b"y")).await.unwrap();
+        record_submission(&db, other, "submitted", None, Some("6Sig")).await.unwrap();
+        assert!(fail_submitted_attestation(&db, other, "rejected on-chain: custom program error").await.unwrap());
+        let subs = list_submissions(&db, other).await.unwrap();
+        assert_eq!(subs.iter().map(|s| s.status.as_str()).collect::<Vec<_>>(), vec!["failed"]);
+        assert!(!confirm_attestation(&db, other, 78).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn burn_and_attestation_are_stored_together() {
+        let db = temp_db().await;
+        let b = burn("tx0", 1, 1_000, "0:MM", "0:AA", "5");
+        assert!(insert_attested_burn(&db, &b, &attestation("ab", "tx0", b"x")).await.unwrap().is_some());
+        // Already stored: nothing new, and no second attestation.
+        assert!(insert_attested_burn(&db, &b, &attestation("cd", "tx0", b"x")).await.unwrap().is_none());
+        assert!(get_attestation_by_hash(&db, "cd").await.unwrap().is_none());
+
+        // A failing attestation insert rolls the burn back with it.
+        sqlx::query("CREATE TRIGGER no_atts BEFORE INSERT ON attestations BEGIN SELECT RAISE(ABORT, 'boom'); END")
+            .execute(&db)
+            .await
+            .unwrap();
+        let b = burn("tx1", 2, 1_001, "0:MM", "0:AA", "5");
+        assert!(insert_attested_burn(&db, &b, &attestation("ef", "tx1", b"x")).await.is_err());
+        assert!(get_burn(&db, "tx1").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn events_replay_after_id() {
+        let db = temp_db().await;
+        let mut ids = Vec::new();
+        for n in 0..3 {
+            ids.push(insert_event(&db, "burn", &format!(r#"{{"n":{n}}}"#)).await.unwrap());
+        }
+        assert!(ids.windows(2).all(|w| w[0] < w[1]));
+        assert_eq!(oldest_event_id(&db).await.unwrap(), Some(ids[0]));
+
+        let after = list_events_after(&db, ids[0], 10).await.unwrap();
+        assert_eq!(after.iter().map(|e| e.id).collect::<Vec<_>>(), vec![ids[1], ids[2]]);
+        assert_eq!(after[0].data, r#"{"n":1}"#);
+
+        assert_eq!(delete_events_before(&db, i64::MAX).await.unwrap(), 3);
+        assert_eq!(oldest_event_id(&db).await.unwrap(), None);
+        // Ids keep increasing after the log is emptied.
+        assert!(insert_event(&db, "burn", "{}").await.unwrap() > ids[2]);
+    }
 }
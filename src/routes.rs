@@ -0,0 +1,111 @@
+use serde::Serialize;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::amounts::{ton_to_sol, Normalized};
+use crate::ton::address::TonAddress;
+
+/// One TON jetton master bridged to one SPL mint.
+#[derive(Debug, Clone, Serialize)]
+pub struct Route {
+    pub name: String,
+    #[serde(serialize_with = "ser_display")]
+    pub ton_master: TonAddress,
+    #[serde(serialize_with = "ser_display")]
+    pub sol_mint: Pubkey,
+    pub decimals_ton: u8,
+    pub decimals_sol: u8,
+    /// Inclusive bounds in TON raw units.
+    #[serde(serialize_with = "ser_display")]
+    pub min_amount_raw: u128,
+    #[serde(serialize_with = "ser_opt_display")]
+    pub max_amount_raw: Option<u128>,
+    /// Accept burns whose amount loses precision on the SPL side (the
+    /// remainder is recorded as dust) instead of rejecting them.
+    pub allow_dust: bool,
+    /// Quarantine burns that carry no `custom_payload`.
+    pub require_payload: bool,
+    pub enabled: bool,
+}
+
+impl Route {
+    /// Per-route limits; an `Err` carries a human-readable reason.
+    pub fn check_amount(&self, amount_raw: u128) -> Result<(), String> {
+        if amount_raw < self.min_amount_raw {
+            return Err(format!("amount {amount_raw} below route minimum {}", self.min_amount_raw));
+        }
+        if let Some(max) = self.max_amount_raw {
+            if amount_raw > max {
+                return Err(format!("amount {amount_raw} above route maximum {max}"));
+            }
+        }
+        Ok(())
+    }
+
+    /// Convert a TON raw amount to SPL base units under this route's policy.
+    pub fn normalize(&self, amount_raw: u128) -> Result<Normalized, String> {
+        let n = ton_to_sol(amount_raw, self.decimals_ton, self.decimals_sol)?;
+        if n.dust_raw > 0 && !self.allow_dust {
+            return Err(format!(
+                "amount {amount_raw} loses precision converting {} -> {} decimals (dust {})",
+                self.decimals_ton, self.decimals_sol, n.dust_raw
+            ));
+        }
+        if n.amount_sol == 0 {
+            return Err(format!("amount {amount_raw} is zero at SPL precision"));
+        }
+        Ok(n)
+    }
+
+    /// Cursor key under which the last processed lt of this route is stored.
+    pub fn cursor_key(&self) -> String {
+        format!("ton_last_lt:{}", self.ton_master.to_raw())
+    }
+}
+
+/// Registry of all configured routes, keyed by TON jetton master.
+#[derive(Debug, Clone, Default)]
+pub struct RouteTable {
+    routes: Vec<Route>,
+}
+
+impl RouteTable {
+    /// Sorts by master so iteration (and the cfg_hash preimage) is canonical.
+    pub fn new(mut routes: Vec<Route>) -> Result<Self, String> {
+        routes.sort_by_key(|r| r.ton_master);
+        if let Some(w) = routes.windows(2).find(|w| w[0].ton_master == w[1].ton_master) {
+            return Err(format!("duplicate route for jetton master {}", w[0].ton_master));
+        }
+        // Pauses, stream filters and stats look routes up by name.
+        let mut names = std::collections::HashSet::new();
+        if let Some(r) = routes.iter().find(|r| !names.insert(&r.name)) {
+            return Err(format!("duplicate route name {:?}", r.name));
+        }
+        Ok(Self { routes })
+    }
+
+    pub fn get(&self, master: &TonAddress) -> Option<&Route> {
+        self.routes
+            .binary_search_by_key(master, |r| r.ton_master)
+            .ok()
+            .map(|i| &self.routes[i])
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Route> {
+        self.routes.iter()
+    }
+
+    pub fn enabled(&self) -> impl Iterator<Item = &Route> {
+        self.routes.iter().filter(|r| r.enabled)
+    }
+}
+
+fn ser_display<T: std::fmt::Display, S: serde::Serializer>(v: &T, s: S) -> Result<S::Ok, S::Error> {
+    s.collect_str(v)
+}
+
+fn ser_opt_display<T: std::fmt::Display, S: serde::Serializer>(v: &Option<T>, s: S) -> Result<S::Ok, S::Error> {
+    match v {
+        Some(v) => s.collect_str(v),
+        None => s.serialize_none(),
+    }
+}
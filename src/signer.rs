@@ -25,15 +25,12 @@ pub fn load_keypair_base64(sk_b64: &str) -> Result<Keypair> {
     }
 }
 
+#[tracing::instrument(skip_all)]
 pub fn sign(hash32: &[u8; 32], kp: &Keypair) -> [u8; 64] {
     let sig: Signature = kp.sign(hash32);
     sig.to_bytes()
 }
 
-pub fn relayer_pubkey_hex(kp: &Keypair) -> String {
-    hex::encode(kp.public.as_bytes())
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
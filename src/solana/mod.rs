@@ -1,3 +1,2 @@
 pub mod watcher;
 pub mod submit;
-pub mod types;
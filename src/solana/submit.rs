@@ -1,14 +1,30 @@
-use anyhow::{anyhow, Result};
 use log::info;
-use borsh::BorshSerialize;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::signature::Signature;
+use solana_transaction_status::TransactionStatus;
+use sqlx::SqlitePool;
+use tokio_util::sync::CancellationToken;
+use tracing::Instrument;
 
-use crate::{attestations::TonToSolAttestationV1, config::Cfg};
+use crate::errors::{RelayerError, Result};
+use crate::events::{ConfirmationEvent, EventBus, RelayerEvent, SubmissionEvent, SubmissionStatus};
+use crate::metrics::METRICS;
+use crate::pause::PauseState;
+use crate::repo::PendingAtt;
+use crate::{attestations::TonToSolAttestation, config::Cfg};
+
+/// Attempts before a retryable submission failure is marked `failed`.
+const MAX_SUBMIT_ATTEMPTS: i64 = 10;
 
 /// Submit a TON→SOL attestation to your Solana bridge program (stub).
 /// Replace with real RPC call building an Instruction & Transaction.
-pub async fn submit_ton_attestation(cfg: &Cfg, att: &TonToSolAttestationV1) -> Result<()> {
-    let bytes = att.try_to_vec().map_err(|e| anyhow!("borsh serialize: {e}"))?;
-    let hash = crate::attestations::domain_hash("TON→SOL_BRIDGE_V1", &bytes);
+///
+/// Returns the transaction signature once one is actually sent.
+#[tracing::instrument(skip_all)]
+pub async fn submit_ton_attestation(cfg: &Cfg, att: &TonToSolAttestation) -> Result<Option<String>> {
+    let bytes = att.to_bytes();
+    let hash = crate::attestations::domain_hash(att.domain(), &bytes);
 
     info!(
         "Would submit attestation to {} ({} bytes, hash: 0x{})",
@@ -16,5 +32,221 @@ pub async fn submit_ton_attestation(cfg: &Cfg, att: &TonToSolAttestationV1) -> R
         bytes.len(),
         hex::encode(hash)
     );
+    Ok(None)
+}
+
+/// Submission worker. Drains `pending` attestations from the database, so
+/// anything not yet sent when shutdown starts stays queued for the next run.
+///
+/// Pauses are checked before every submission; attestations of paused
+/// routes stay `pending` until resumed. Each pass also checks the status of
+/// transactions already sent (see [`check_confirmations`]).
+pub async fn run(
+    cfg: Cfg,
+    db: SqlitePool,
+    bus: EventBus,
+    shutdown: CancellationToken,
+) -> Result<()> {
+    let rpc = RpcClient::new_with_commitment(cfg.sol_rpc_http.to_string(), CommitmentConfig::finalized());
+    while !shutdown.is_cancelled() {
+        let paused = PauseState::load(&db).await?;
+        let skip: Vec<String> = cfg
+            .routes
+            .iter()
+            .filter(|r| paused.route_paused(&r.name))
+            .map(|r| r.ton_master.to_raw())
+            .collect();
+        let rows = if paused.global.is_some() {
+            Vec::new()
+        } else {
+            crate::repo::list_pending_attestations(&db, &skip, 20).await?
+        };
+        for row in rows {
+            // Finish the in-flight submission, but do not start new ones.
+            if shutdown.is_cancelled() {
+                break;
+            }
+            // A switch flipped mid-batch ends it; the next pass re-reads.
+            if PauseState::load(&db).await? != paused {
+                break;
+            }
+            let span = tracing::info_span!(
+                "submit",
+                att_hash = %row.payload_hash_hex,
+                ton_tx = row.src_tx_hash_b64.as_deref().unwrap_or("?"),
+                attempt = row.attempts + 1,
+            );
+            submit_row(&cfg, &db, &bus, row).instrument(span).await?;
+        }
+        if let Err(e) = check_confirmations(&cfg, &db, &bus, &rpc).await {
+            if !e.is_retryable() {
+                return Err(e);
+            }
+            tracing::warn!("confirmation check failed ({}), retrying: {e}", e.kind());
+        }
+
+        tokio::select! {
+            _ = tokio::time::sleep(std::time::Duration::from_secs(1)) => {}
+            _ = shutdown.cancelled() => {}
+        }
+    }
+    info!("Solana submitter stopped");
     Ok(())
 }
+
+async fn submit_row(
+    cfg: &Cfg,
+    db: &SqlitePool,
+    bus: &EventBus,
+    row: PendingAtt,
+) -> Result<()> {
+    let decoded = TonToSolAttestation::decode(&row.payload_borsh);
+    let (status, error, sol_sig) = match &decoded {
+        Err(e) => ("failed", Some(format!("stored payload does not decode: {e}")), None),
+        Ok(att) => match submit_ton_attestation(cfg, att).await {
+            Ok(sig) => ("submitted", None, sig),
+            Err(e) if e.is_retryable() && row.attempts + 1 < MAX_SUBMIT_ATTEMPTS => {
+                tracing::warn!("submit {} failed, will retry: {e}", row.payload_hash_hex);
+                ("pending", Some(e.to_string()), None)
+            }
+            Err(e) => ("failed", Some(e.to_string()), None),
+        },
+    };
+    crate::repo::record_submission(db, row.id, status, error.as_deref(), sol_sig.as_deref()).await?;
+
+    if status != "pending" {
+        METRICS.attestations.with_label_values(&[status]).inc();
+        let status = if status == "submitted" { SubmissionStatus::Submitted } else { SubmissionStatus::Failed };
+        bus.emit(submission_event(cfg, row, status, error, sol_sig)).await;
+    }
+    Ok(())
+}
+
+fn submission_event(
+    cfg: &Cfg,
+    row: PendingAtt,
+    status: SubmissionStatus,
+    error: Option<String>,
+    sol_signature: Option<String>,
+) -> RelayerEvent {
+    let (route, dst) = route_and_dst(cfg, &row.payload_borsh);
+    RelayerEvent::Submission(SubmissionEvent {
+        hash_hex: row.payload_hash_hex,
+        src_tx: row.src_tx_hash_b64,
+        owner: row.owner_raw,
+        route,
+        dst,
+        status,
+        error,
+        sol_signature,
+    })
+}
+
+fn confirmation_event(cfg: &Cfg, row: PendingAtt, slot: u64) -> RelayerEvent {
+    let (route, dst) = route_and_dst(cfg, &row.payload_borsh);
+    RelayerEvent::Confirmation(ConfirmationEvent {
+        hash_hex: row.payload_hash_hex,
+        sol_signature: row.sol_signature.unwrap_or_default(),
+        slot,
+        src_tx: row.src_tx_hash_b64,
+        owner: row.owner_raw,
+        route,
+        dst,
+    })
+}
+
+/// Route name and destination account of a stored payload, when it decodes.
+fn route_and_dst(cfg: &Cfg, payload: &[u8]) -> (Option<String>, Option<String>) {
+    let att = TonToSolAttestation::decode(payload).ok();
+    let route = att.as_ref().and_then(|a| {
+        let master = crate::ton::address::TonAddress::from_wc_hash36(&a.jetton_minter_ton());
+        cfg.routes.get(&master).map(|r| r.name.clone())
+    });
+    let dst = att.map(|a| solana_sdk::pubkey::Pubkey::new_from_array(a.dst_solana_pubkey()).to_string());
+    (route, dst)
+}
+
+/// What a signature status says about a sent transaction: the slot once it
+/// is finalized, `None` while it is not (or not yet seen), and
+/// [`RelayerError::Rejected`] if it failed on chain.
+fn settle(status: Option<&TransactionStatus>) -> Result<Option<u64>> {
+    let Some(status) = status else {
+        return Ok(None);
+    };
+    if let Some(err) = &status.err {
+        return Err(RelayerError::Rejected(err.to_string()));
+    }
+    Ok(status.satisfies_commitment(CommitmentConfig::finalized()).then_some(status.slot))
+}
+
+/// Look up the transactions of submitted attestations. Finalized ones are
+/// recorded and reported as `confirmation` events; ones the program
+/// rejected are marked `failed` so they can be rebuilt or resubmitted. The
+/// rest are checked again on the next pass.
+async fn check_confirmations(cfg: &Cfg, db: &SqlitePool, bus: &EventBus, rpc: &RpcClient) -> Result<()> {
+    let rows = crate::repo::list_unconfirmed_attestations(db, 100).await?;
+    if rows.is_empty() {
+        return Ok(());
+    }
+    let sigs = rows
+        .iter()
+        .map(|r| r.sol_signature.as_deref().unwrap_or_default().parse::<Signature>())
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .map_err(|e| RelayerError::Decode(format!("stored signature: {e}")))?;
+    let statuses = crate::health::rpc_call("getSignatureStatuses", rpc.get_signature_statuses_with_history(&sigs))
+        .await?
+        .value;
+
+    for (row, status) in rows.into_iter().zip(statuses) {
+        match settle(status.as_ref()) {
+            Ok(None) => {}
+            Ok(Some(slot)) => {
+                if crate::repo::confirm_attestation(db, row.id, slot as i64).await? {
+                    info!("attestation {} finalized in slot {slot}", row.payload_hash_hex);
+                    METRICS.attestations.with_label_values(&["confirmed"]).inc();
+                    bus.emit(confirmation_event(cfg, row, slot)).await;
+                }
+            }
+            Err(e) => {
+                let error = e.to_string();
+                if crate::repo::fail_submitted_attestation(db, row.id, &error).await? {
+                    tracing::warn!("attestation {} {error}", row.payload_hash_hex);
+                    METRICS.attestations.with_label_values(&["failed"]).inc();
+                    let sig = row.sol_signature.clone();
+                    bus.emit(submission_event(cfg, row, SubmissionStatus::Failed, Some(error), sig)).await;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::instruction::InstructionError;
+    use solana_sdk::transaction::TransactionError;
+    use solana_transaction_status::TransactionConfirmationStatus;
+
+    fn status(level: TransactionConfirmationStatus, err: Option<TransactionError>) -> TransactionStatus {
+        TransactionStatus {
+            slot: 42,
+            // The RPC reports `None` once the slot is rooted.
+            confirmations: (level != TransactionConfirmationStatus::Finalized).then_some(1),
+            status: err.clone().map_or(Ok(()), Err),
+            err,
+            confirmation_status: Some(level),
+        }
+    }
+
+    #[test]
+    fn only_finalized_transactions_settle_and_program_errors_are_rejections() {
+        assert!(matches!(settle(None), Ok(None)));
+        assert!(matches!(settle(Some(&status(TransactionConfirmationStatus::Confirmed, None))), Ok(None)));
+        assert!(matches!(settle(Some(&status(TransactionConfirmationStatus::Finalized, None))), Ok(Some(42))));
+
+        let err = TransactionError::InstructionError(0, InstructionError::Custom(6001));
+        let rejected = settle(Some(&status(TransactionConfirmationStatus::Confirmed, Some(err))));
+        assert!(matches!(rejected, Err(RelayerError::Rejected(ref m)) if m.contains("custom program error: 0x1771")), "{rejected:?}");
+    }
+}
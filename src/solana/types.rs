@@ -1,6 +0,0 @@
-use solana_sdk::pubkey::Pubkey;
-
-#[derive(Clone, Debug)]
-pub struct ProgramIds {
-    pub bridge_program: Pubkey,
-}
@@ -0,0 +1,317 @@
+use serde::Serialize;
+use sqlx::SqlitePool;
+use std::collections::BTreeMap;
+use utoipa::ToSchema;
+
+use crate::config::Cfg;
+use crate::errors::Result;
+use crate::repo::{self, AmountSum, VolumeRow};
+use crate::ton::address::TonAddress;
+
+const HOUR: i64 = 3600;
+const DAY: i64 = 86_400;
+
+/// How far back `GET /stats` looks. Hourly buckets cover `hours`; daily
+/// buckets, outcomes, finalization times and destinations cover `days`.
+#[derive(Debug, Clone, Copy)]
+pub struct Window {
+    pub hours: i64,
+    pub days: i64,
+    /// Destinations listed per route.
+    pub top: i64,
+}
+
+impl Window {
+    pub fn new(hours: Option<i64>, days: Option<i64>, top: Option<i64>) -> Self {
+        Self {
+            hours: hours.unwrap_or(48).clamp(1, 24 * 14),
+            days: days.unwrap_or(30).clamp(1, 366),
+            top: top.unwrap_or(10).clamp(1, 100),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct Stats {
+    /// Unix time the figures were computed at.
+    pub generated_at: i64,
+    pub hours: i64,
+    pub days: i64,
+    pub totals: Totals,
+    /// Keyed by route name; burns of unconfigured masters by the raw address.
+    pub routes: BTreeMap<String, RouteStats>,
+    /// Across all routes, over `days`.
+    pub finalization: Option<Finalization>,
+    /// Solana submission attempts over `days`, retries included.
+    pub submissions: Submissions,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct Totals {
+    /// All time.
+    pub burns: i64,
+    /// All time, by status (`unknown` for rows that predate statuses).
+    pub attestations: BTreeMap<String, i64>,
+}
+
+#[derive(Debug, Default, Serialize, ToSchema)]
+pub struct RouteStats {
+    /// All time.
+    pub burns: i64,
+    /// All time, TON raw units.
+    pub amount_raw: String,
+    /// Oldest first, one per hour including empty ones; the last is partial.
+    pub hourly: Vec<Bucket>,
+    /// Oldest first, one per UTC day including empty ones; the last is partial.
+    pub daily: Vec<Bucket>,
+    pub outcomes: Outcomes,
+    pub finalization: Option<Finalization>,
+    pub top_destinations: Vec<Destination>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct Bucket {
+    /// Unix time the bucket starts at.
+    pub start: i64,
+    pub burns: i64,
+    /// TON raw units.
+    pub amount_raw: String,
+}
+
+/// What became of the route's attestations created within `days`.
+#[derive(Debug, Default, Serialize, ToSchema)]
+pub struct Outcomes {
+    pub attestations: i64,
+    pub submitted: i64,
+    pub failed: i64,
+    pub pending: i64,
+    /// Burns held back rather than attested.
+    pub quarantined: i64,
+    /// `failed / (submitted + failed)`; null before anything settled.
+    pub failure_rate: Option<f64>,
+    /// `quarantined / (attestations + quarantined)`.
+    pub quarantine_rate: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct Finalization {
+    /// Median seconds from the TON burn to the finalization of its Solana
+    /// transaction.
+    pub median_secs: f64,
+    /// Transfers the median is taken over.
+    pub transfers: i64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct Destination {
+    /// Solana account, base58.
+    pub dst: String,
+    pub transfers: i64,
+    /// SPL units of the route's mint.
+    pub amount_dst: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct Submissions {
+    pub attempts: i64,
+    pub failed: i64,
+    pub failure_rate: Option<f64>,
+}
+
+fn ratio(part: i64, whole: i64) -> Option<f64> {
+    (whole > 0).then(|| part as f64 / whole as f64)
+}
+
+/// Route name for a stored master address, or the address itself.
+fn route_name(cfg: &Cfg, master_raw: &str) -> String {
+    master_raw
+        .parse::<TonAddress>()
+        .ok()
+        .and_then(|a| cfg.routes.get(&a))
+        .map(|r| r.name.clone())
+        .unwrap_or_else(|| master_raw.to_string())
+}
+
+/// One bucket per `size` from `first` to `now` over the burns of `masters`,
+/// zero where nothing burned.
+fn fill(rows: &[VolumeRow], masters: &[String], first: i64, now: i64, size: i64) -> Vec<Bucket> {
+    let mut sums: BTreeMap<i64, (i64, AmountSum)> = BTreeMap::new();
+    for r in rows.iter().filter(|r| masters.contains(&r.jetton_master_raw)) {
+        let s = sums.entry(r.bucket).or_default();
+        *s = (s.0 + r.count, s.1 + r.amount);
+    }
+    (first..=now)
+        .step_by(size as usize)
+        .map(|start| {
+            let (burns, amount) = sums.get(&start).copied().unwrap_or_default();
+            Bucket { start, burns, amount_raw: amount.to_string() }
+        })
+        .collect()
+}
+
+/// Aggregate `burns` and `attestations` for `GET /stats`. Every figure is a
+/// grouped query over indexed columns; nothing is cached.
+pub async fn compute(cfg: &Cfg, db: &SqlitePool, w: Window, now: i64) -> Result<Stats> {
+    let first_hour = now / HOUR * HOUR - (w.hours - 1) * HOUR;
+    let first_day = now / DAY * DAY - (w.days - 1) * DAY;
+
+    let hourly = repo::burn_volume(db, HOUR, first_hour).await?;
+    let daily = repo::burn_volume(db, DAY, first_day).await?;
+    let totals = repo::burn_totals(db).await?;
+    let outcomes = repo::attestation_outcomes(db, first_day).await?;
+    let quarantined = repo::quarantine_counts(db, first_day).await?;
+    let (attempts, failed) = repo::submission_attempts(db, first_day).await?;
+
+    // Masters with any activity, grouped by the name they are reported
+    // under. Rows stored before addresses were canonical can put one route
+    // under several spellings of its master.
+    let mut masters: BTreeMap<String, Vec<String>> = cfg.routes.iter().map(|r| (r.name.clone(), Vec::new())).collect();
+    let seen = totals.iter().map(|t| &t.0).chain(quarantined.iter().map(|q| &q.0));
+    for m in seen {
+        let group = masters.entry(route_name(cfg, m)).or_default();
+        if !group.contains(m) {
+            group.push(m.clone());
+        }
+    }
+    let mut routes = BTreeMap::new();
+    for (name, masters) in masters {
+        let mine = |m: &String| masters.contains(m);
+        let (burns, amount) = totals
+            .iter()
+            .filter(|t| mine(&t.0))
+            .fold((0, AmountSum::default()), |(n, sum), t| (n + t.1, sum + t.2));
+        let mut o = Outcomes::default();
+        for row in outcomes.iter().filter(|o| mine(&o.jetton_master_raw)) {
+            o.attestations += row.attestations;
+            o.submitted += row.submitted;
+            o.failed += row.failed;
+            o.pending += row.pending;
+        }
+        o.quarantined = quarantined.iter().filter(|q| mine(&q.0)).map(|q| q.1).sum();
+        o.failure_rate = ratio(o.failed, o.submitted + o.failed);
+        o.quarantine_rate = ratio(o.quarantined, o.attestations + o.quarantined);
+        let finalization = if masters.is_empty() {
+            None
+        } else {
+            repo::finalization_median(db, first_day, Some(&masters)).await?
+        };
+        let top_destinations = repo::top_destinations(db, &masters, first_day, w.top)
+            .await?
+            .into_iter()
+            .map(|d| Destination {
+                dst: bs58::encode(d.dst).into_string(),
+                transfers: d.count,
+                amount_dst: d.amount.to_string(),
+            })
+            .collect();
+        let stats = RouteStats {
+            burns,
+            amount_raw: amount.to_string(),
+            hourly: fill(&hourly, &masters, first_hour, now, HOUR),
+            daily: fill(&daily, &masters, first_day, now, DAY),
+            outcomes: o,
+            finalization: finalization.map(|f| Finalization { median_secs: f.median_secs, transfers: f.count }),
+            top_destinations,
+        };
+        routes.insert(name, stats);
+    }
+
+    let attestations = repo::attestation_status_counts(db)
+        .await?
+        .into_iter()
+        .map(|(status, n)| (status.unwrap_or_else(|| "unknown".into()), n))
+        .collect();
+    Ok(Stats {
+        generated_at: now,
+        hours: w.hours,
+        days: w.days,
+        totals: Totals { burns: totals.iter().map(|t| t.1).sum(), attestations },
+        routes,
+        finalization: repo::finalization_median(db, first_day, None)
+            .await?
+            .map(|f| Finalization { median_secs: f.median_secs, transfers: f.count }),
+        submissions: Submissions { attempts, failed, failure_rate: ratio(failed, attempts) },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn aggregates_volume_outcomes_and_destinations_per_route() {
+        let cfg = crate::config::tests::cfg_with("");
+        let db = repo::tests::temp_db().await;
+        let route = cfg.routes.iter().next().unwrap();
+        let master = route.ton_master.to_raw();
+        // As stored before addresses were canonicalized.
+        let legacy = route.ton_master.to_friendly(true, false);
+        let other = format!("0:{}", "CD".repeat(32));
+        let now = crate::utils::now_ts() as i64;
+        let this_hour = now / HOUR * HOUR;
+        // Wider than i64 alone, and than u128 once summed.
+        let big = "300000000000000000000000000000000000000";
+
+        let mut payload = vec![2u8; crate::attestations::DST_OFFSET];
+        payload.extend([1u8; 32]);
+        payload.extend([0u8; 40]);
+        for (i, (m, utime, amount, outcome)) in [
+            (&master, this_hour, big, Some("submitted")),
+            (&legacy, now - 2 * HOUR, "100000000000000000000000000000000000005", Some("failed")),
+            (&other, now - 3 * DAY, "9", None),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            let tx = format!("tx{i}");
+            repo::insert_burn(&db, &repo::tests::burn(&tx, i as i64, utime, m, "0:AA", amount)).await.unwrap();
+            if let Some(status) = outcome {
+                let hash = format!("h{i}");
+                let id = repo::insert_attestation(&db, &repo::tests::attestation(&hash, &tx, &payload)).await.unwrap();
+                repo::record_submission(&db, id, status, None, Some("sig")).await.unwrap();
+                if status == "submitted" {
+                    let f = compute(&cfg, &db, Window::new(None, None, None), now).await.unwrap();
+                    assert!(f.finalization.is_none(), "submitted is not finalized");
+                    assert!(repo::confirm_attestation(&db, id, 1).await.unwrap());
+                }
+            }
+        }
+        let q = repo::NewQuarantine {
+            qkey: "q1",
+            tx_hash_b64: Some("tx9"),
+            jetton_master_raw: &master,
+            reason_code: "no_payload",
+            reason: "no payload",
+            releasable: true,
+            raw_json: "{}",
+        };
+        repo::insert_quarantine(&db, &q).await.unwrap();
+
+        let s = compute(&cfg, &db, Window::new(None, None, None), now).await.unwrap();
+        assert_eq!(s.totals.burns, 3);
+        assert_eq!((s.totals.attestations["submitted"], s.totals.attestations["failed"]), (1, 1));
+        assert_eq!((s.submissions.attempts, s.submissions.failed, s.submissions.failure_rate), (2, 1, Some(0.5)));
+
+        let r = &s.routes[&route.name];
+        assert_eq!(r.burns, 2);
+        assert_eq!(r.amount_raw, "400000000000000000000000000000000000005");
+        assert_eq!((r.hourly.len(), r.daily.len()), (48, 30));
+        assert_eq!(r.hourly.last().unwrap().amount_raw, big);
+        assert_eq!(r.hourly.iter().map(|b| b.burns).sum::<i64>(), 2);
+        assert_eq!((r.outcomes.submitted, r.outcomes.failed, r.outcomes.quarantined), (1, 1, 1));
+        assert_eq!(r.outcomes.failure_rate, Some(0.5));
+        let f = r.finalization.as_ref().unwrap();
+        assert!(f.transfers == 1 && f.median_secs >= (now - this_hour) as f64);
+        assert_eq!(r.top_destinations.len(), 1);
+        let d = &r.top_destinations[0];
+        assert_eq!(d.dst, bs58::encode([1u8; 32]).into_string());
+        assert_eq!((d.transfers, d.amount_dst.as_str()), (2, "10"));
+
+        assert!(!s.routes.contains_key(&legacy));
+
+        // Burns of unconfigured masters are reported under the raw address.
+        let o = &s.routes[&other];
+        let burns = |b: &[Bucket]| b.iter().map(|b| b.burns).sum::<i64>();
+        assert_eq!((o.burns, burns(&o.hourly), burns(&o.daily)), (1, 0, 1));
+    }
+}
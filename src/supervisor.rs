@@ -0,0 +1,293 @@
+use serde::Serialize;
+use std::{
+    collections::BTreeMap,
+    fmt::Display,
+    future::Future,
+    str::FromStr,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+use tracing::{error, info, warn};
+
+use crate::utils::now_ts;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WorkerState {
+    Starting,
+    Running,
+    /// Failed and waiting to be restarted.
+    Backoff,
+    /// Exited cleanly and will not be restarted.
+    Stopped,
+    /// Gave up after exhausting the restart policy.
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WorkerStatus {
+    pub name: &'static str,
+    pub state: WorkerState,
+    pub restarts: u32,
+    pub last_error: Option<String>,
+    pub last_error_at: Option<u64>,
+    pub started_at: Option<u64>,
+}
+
+/// When a worker that returned is started again. Failures count towards
+/// `max_restarts` either way; `Never` gives up on the first one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Restart {
+    Always,
+    OnFailure,
+    Never,
+}
+
+impl FromStr for Restart {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        match s.trim() {
+            "always" => Ok(Restart::Always),
+            "on_failure" | "on-failure" => Ok(Restart::OnFailure),
+            "never" => Ok(Restart::Never),
+            _ => Err(format!("unknown restart policy {s:?}, expected always, on_failure or never")),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RestartPolicy {
+    pub restart: Restart,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    /// Consecutive failures tolerated before the worker is marked failed.
+    /// `None` retries forever.
+    pub max_restarts: Option<u32>,
+}
+
+impl RestartPolicy {
+    fn backoff(&self, failures: u32) -> Duration {
+        let exp = failures.saturating_sub(1).min(16);
+        self.initial_backoff.saturating_mul(1 << exp).min(self.max_backoff)
+    }
+}
+
+/// Owns the long-running workers, restarts them per their policy and keeps
+/// a status table for `/health`.
+#[derive(Clone, Default)]
+pub struct Supervisor {
+    workers: Arc<Mutex<BTreeMap<&'static str, WorkerStatus>>>,
+    handles: Arc<Mutex<Vec<JoinHandle<()>>>>,
+    shutdown: CancellationToken,
+}
+
+impl Supervisor {
+    /// Workers are handed `shutdown` and are not restarted once it is cancelled.
+    pub fn new(shutdown: CancellationToken) -> Self {
+        Self { shutdown, ..Self::default() }
+    }
+
+    /// Run `make(shutdown)` under supervision. `make` is called again for every restart.
+    pub fn spawn<F, Fut, E>(&self, name: &'static str, policy: RestartPolicy, make: F)
+    where
+        F: Fn(CancellationToken) -> Fut + Send + 'static,
+        Fut: Future<Output = Result<(), E>> + Send + 'static,
+        E: Display + Send + 'static,
+    {
+        self.update(name, |w| w.state = WorkerState::Starting);
+        let sup = self.clone();
+        let handle = tokio::spawn(async move {
+            let mut failures = 0u32;
+            loop {
+                if sup.shutdown.is_cancelled() {
+                    sup.update(name, |w| w.state = WorkerState::Stopped);
+                    return;
+                }
+                sup.update(name, |w| {
+                    w.state = WorkerState::Running;
+                    w.started_at = Some(now_ts());
+                });
+                let started = Instant::now();
+
+                // Run in a child task so a panic is reported instead of killing the supervisor.
+                let outcome = match tokio::spawn(make(sup.shutdown.clone())).await {
+                    Ok(Ok(())) => None,
+                    Ok(Err(e)) => Some(e.to_string()),
+                    Err(join) => Some(format!("panicked: {join}")),
+                };
+
+                if sup.shutdown.is_cancelled() {
+                    match &outcome {
+                        None => info!("worker {name} stopped for shutdown"),
+                        Some(err) => warn!("worker {name} failed during shutdown: {err}"),
+                    }
+                    sup.update(name, |w| {
+                        if let Some(err) = &outcome {
+                            w.last_error = Some(err.clone());
+                            w.last_error_at = Some(now_ts());
+                        }
+                        w.state = WorkerState::Stopped;
+                    });
+                    return;
+                }
+
+                let restart = match policy.restart {
+                    Restart::Always => true,
+                    Restart::OnFailure => outcome.is_some(),
+                    Restart::Never => false,
+                };
+                let Some(err) = outcome else {
+                    if !restart {
+                        info!("worker {name} exited");
+                        sup.update(name, |w| w.state = WorkerState::Stopped);
+                        return;
+                    }
+                    warn!("worker {name} exited, restarting");
+                    sup.pause(policy.initial_backoff).await;
+                    sup.update(name, |w| w.restarts += 1);
+                    continue;
+                };
+
+                // A worker that stayed up longer than the max backoff starts a fresh failure streak.
+                if started.elapsed() > policy.max_backoff {
+                    failures = 0;
+                }
+                failures += 1;
+                let give_up = !restart || policy.max_restarts.is_some_and(|m| failures > m);
+                sup.update(name, |w| {
+                    w.last_error = Some(err.clone());
+                    w.last_error_at = Some(now_ts());
+                    w.state = if give_up { WorkerState::Failed } else { WorkerState::Backoff };
+                });
+                if give_up {
+                    error!("worker {name} failed permanently after {failures} failure(s): {err}");
+                    return;
+                }
+
+                let delay = policy.backoff(failures);
+                warn!("worker {name} failed ({failures} in a row), restarting in {delay:?}: {err}");
+                sup.pause(delay).await;
+                sup.update(name, |w| w.restarts += 1);
+            }
+        });
+        self.handles.lock().expect("supervisor lock").push(handle);
+    }
+
+    /// Wait for every spawned worker to finish. Call after cancelling the
+    /// shutdown token, otherwise workers with `Restart::Always` never return.
+    pub async fn join_all(&self) {
+        let handles = std::mem::take(&mut *self.handles.lock().expect("supervisor lock"));
+        for h in handles {
+            let _ = h.await;
+        }
+    }
+
+    /// Backoff sleep that ends early on shutdown; the loop then sees the
+    /// cancelled token on the next iteration and stops without restarting.
+    async fn pause(&self, d: Duration) {
+        tokio::select! {
+            _ = tokio::time::sleep(d) => {}
+            _ = self.shutdown.cancelled() => {}
+        }
+    }
+
+    pub fn statuses(&self) -> Vec<WorkerStatus> {
+        self.workers.lock().expect("supervisor lock").values().cloned().collect()
+    }
+
+    fn update(&self, name: &'static str, f: impl FnOnce(&mut WorkerStatus)) {
+        let mut workers = self.workers.lock().expect("supervisor lock");
+        let w = workers.entry(name).or_insert_with(|| WorkerStatus {
+            name,
+            state: WorkerState::Starting,
+            restarts: 0,
+            last_error: None,
+            last_error_at: None,
+            started_at: None,
+        });
+        f(w);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    fn policy(max_restarts: Option<u32>) -> RestartPolicy {
+        RestartPolicy {
+            restart: Restart::OnFailure,
+            initial_backoff: Duration::from_millis(1),
+            max_backoff: Duration::from_millis(4),
+            max_restarts,
+        }
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_max() {
+        let p = policy(None);
+        let d: Vec<_> = (1..=5).map(|n| p.backoff(n).as_millis()).collect();
+        assert_eq!(d, vec![1, 2, 4, 4, 4]);
+    }
+
+    #[tokio::test]
+    async fn restarts_until_success_then_stops() {
+        let sup = Supervisor::default();
+        let calls = Arc::new(AtomicU32::new(0));
+        let c = calls.clone();
+        sup.spawn("flaky", policy(None), move |_| {
+            let c = c.clone();
+            async move {
+                if c.fetch_add(1, Ordering::SeqCst) < 2 { Err("boom") } else { Ok(()) }
+            }
+        });
+        sup.join_all().await;
+
+        let w = &sup.statuses()[0];
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+        assert_eq!((w.state, w.restarts), (WorkerState::Stopped, 2));
+        assert_eq!(w.last_error.as_deref(), Some("boom"));
+    }
+
+    #[tokio::test]
+    async fn gives_up_after_max_restarts() {
+        let sup = Supervisor::default();
+        sup.spawn("broken", policy(Some(1)), |_| async { Err::<(), _>("nope") });
+        sup.join_all().await;
+        let w = &sup.statuses()[0];
+        assert_eq!((w.state, w.restarts), (WorkerState::Failed, 1));
+    }
+
+    #[tokio::test]
+    async fn never_gives_up_on_the_first_failure() {
+        let sup = Supervisor::default();
+        let mut p = policy(None);
+        p.restart = Restart::Never;
+        sup.spawn("once", p, |_| async { Err::<(), _>("nope") });
+        sup.join_all().await;
+        let w = &sup.statuses()[0];
+        assert_eq!((w.state, w.restarts), (WorkerState::Failed, 0));
+        assert_eq!("on_failure".parse::<Restart>(), Ok(Restart::OnFailure));
+        assert!("sometimes".parse::<Restart>().is_err());
+    }
+
+    #[tokio::test]
+    async fn shutdown_stops_workers_without_restart() {
+        let token = CancellationToken::new();
+        let sup = Supervisor::new(token.clone());
+        let mut p = policy(None);
+        p.restart = Restart::Always;
+        sup.spawn("looper", p, |shutdown: CancellationToken| async move {
+            shutdown.cancelled().await;
+            Ok::<(), &str>(())
+        });
+        tokio::task::yield_now().await;
+        token.cancel();
+        tokio::time::timeout(Duration::from_secs(1), sup.join_all()).await.unwrap();
+        let w = &sup.statuses()[0];
+        assert_eq!((w.state, w.restarts), (WorkerState::Stopped, 0));
+    }
+}
@@ -0,0 +1,136 @@
+use anyhow::{anyhow, Result};
+use base64::Engine;
+use std::{fmt, str::FromStr};
+
+/// A TON account address (workchain + 256-bit account id).
+///
+/// Accepts both the raw form (`0:83df…`) and the 48-char user-friendly form
+/// (standard or URL-safe base64, any flags).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TonAddress {
+    pub workchain: i32,
+    pub hash: [u8; 32],
+}
+
+const TAG_BOUNCEABLE: u8 = 0x11;
+const TAG_NON_BOUNCEABLE: u8 = 0x51;
+const TAG_TESTNET: u8 = 0x80;
+
+impl TonAddress {
+    /// Canonical 36-byte encoding used in attestations: workchain as i32 BE, then hash.
+    pub fn wc_hash36(&self) -> [u8; 36] {
+        let mut out = [0u8; 36];
+        out[..4].copy_from_slice(&self.workchain.to_be_bytes());
+        out[4..].copy_from_slice(&self.hash);
+        out
+    }
+
+    pub fn from_wc_hash36(b: &[u8; 36]) -> Self {
+        let mut wc = [0u8; 4];
+        wc.copy_from_slice(&b[..4]);
+        let mut hash = [0u8; 32];
+        hash.copy_from_slice(&b[4..]);
+        Self { workchain: i32::from_be_bytes(wc), hash }
+    }
+
+    /// `wc:HEX` form as returned by toncenter v3.
+    pub fn to_raw(self) -> String {
+        format!("{}:{}", self.workchain, hex::encode_upper(self.hash))
+    }
+
+    /// URL-safe user-friendly form.
+    pub fn to_friendly(self, bounceable: bool, testnet: bool) -> String {
+        let mut b = [0u8; 36];
+        b[0] = if bounceable { TAG_BOUNCEABLE } else { TAG_NON_BOUNCEABLE };
+        if testnet {
+            b[0] |= TAG_TESTNET;
+        }
+        b[1] = self.workchain as i8 as u8;
+        b[2..34].copy_from_slice(&self.hash);
+        let crc = crc16_xmodem(&b[..34]);
+        b[34..].copy_from_slice(&crc.to_be_bytes());
+        base64::engine::general_purpose::URL_SAFE.encode(b)
+    }
+
+    fn parse_raw(s: &str) -> Result<Self> {
+        let (wc, h) = s.split_once(':').ok_or_else(|| anyhow!("raw address must be wc:hex"))?;
+        let workchain: i32 = wc.parse().map_err(|e| anyhow!("bad workchain {wc:?}: {e}"))?;
+        let hash = crate::utils::hex32(h)?;
+        Ok(Self { workchain, hash })
+    }
+
+    fn parse_friendly(s: &str) -> Result<Self> {
+        let bytes = base64::engine::general_purpose::URL_SAFE
+            .decode(s)
+            .or_else(|_| base64::engine::general_purpose::STANDARD.decode(s))
+            .map_err(|e| anyhow!("bad base64 address: {e}"))?;
+        if bytes.len() != 36 {
+            return Err(anyhow!("user-friendly address must be 36 bytes, got {}", bytes.len()));
+        }
+        let tag = bytes[0] & !TAG_TESTNET;
+        if tag != TAG_BOUNCEABLE && tag != TAG_NON_BOUNCEABLE {
+            return Err(anyhow!("unknown address tag 0x{:02x}", bytes[0]));
+        }
+        let crc = u16::from_be_bytes([bytes[34], bytes[35]]);
+        if crc != crc16_xmodem(&bytes[..34]) {
+            return Err(anyhow!("address checksum mismatch"));
+        }
+        let mut hash = [0u8; 32];
+        hash.copy_from_slice(&bytes[2..34]);
+        Ok(Self { workchain: bytes[1] as i8 as i32, hash })
+    }
+}
+
+impl FromStr for TonAddress {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+        if s.contains(':') { Self::parse_raw(s) } else { Self::parse_friendly(s) }
+    }
+}
+
+impl fmt::Display for TonAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_raw())
+    }
+}
+
+fn crc16_xmodem(data: &[u8]) -> u16 {
+    let mut crc: u16 = 0;
+    for &b in data {
+        crc ^= (b as u16) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 { (crc << 1) ^ 0x1021 } else { crc << 1 };
+        }
+    }
+    crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn crc16_check_value() {
+        assert_eq!(crc16_xmodem(b"123456789"), 0x31C3);
+    }
+
+    #[test]
+    fn raw_and_friendly_round_trip() {
+        let raw = "0:83DFD552E63729B472FCBCC8C45EBCC6691702558B68EC7527E1BA403A0F31A8";
+        let a: TonAddress = raw.parse().unwrap();
+        assert_eq!(a.to_raw(), raw);
+
+        let friendly = a.to_friendly(true, false);
+        assert_eq!(friendly, "EQCD39VS5jcptHL8vMjEXrzGaRcCVYto7HUn4bpAOg8xqB2N");
+        assert_eq!(friendly.parse::<TonAddress>().unwrap(), a);
+        assert_eq!(a.to_friendly(false, true).parse::<TonAddress>().unwrap(), a);
+        assert_eq!(TonAddress::from_wc_hash36(&a.wc_hash36()), a);
+    }
+
+    #[test]
+    fn rejects_bad_checksum() {
+        assert!("EQCD39VS5jcptHL8vMjEXrzGaRcCVYto7HUn4bpAOg8xqB2M".parse::<TonAddress>().is_err());
+    }
+}
@@ -1,2 +1,2 @@
 pub mod watcher;
-pub mod client;
+pub mod address;
@@ -1,8 +1,12 @@
-use anyhow::{Context, Result};
 use serde::Deserialize;
 use tracing::info;
-use base64::Engine; // <-- add
 
+use crate::config::Cfg;
+use crate::errors::{RelayerError, Result};
+use crate::metrics::METRICS;
+use crate::ton::address::TonAddress;
+
+/// A burn that passed validation, ready to be stored and attested.
 #[derive(Clone, Debug)]
 pub struct TonBurn {
     pub tx_hash_b64: String,
@@ -15,45 +19,86 @@ pub struct TonBurn {
     pub custom_payload: Option<Vec<u8>>,
 }
 
+/// One burn exactly as reported by the provider, before any validation.
+#[derive(Clone, Debug)]
+pub struct RawBurn(pub serde_json::Value);
+
+impl RawBurn {
+    pub fn str(&self, key: &str) -> Option<&str> {
+        self.0.get(key)?.as_str()
+    }
+
+    /// Accepts both JSON numbers and numeric strings (toncenter sends lt as a string).
+    pub fn u64(&self, key: &str) -> Option<Result<u64, String>> {
+        json_u64(&self.0, key)
+    }
+
+    pub fn tx_hash_b64(&self) -> Option<&str> {
+        self.str("transaction_hash")
+    }
+}
+
+fn json_u64(obj: &serde_json::Value, key: &str) -> Option<Result<u64, String>> {
+    let v = obj.get(key)?;
+    Some(match v {
+        serde_json::Value::Number(n) => n.as_u64().ok_or_else(|| format!("{key}: {n} is not a u64")),
+        serde_json::Value::String(s) => s.parse().map_err(|e| format!("{key}: {s:?}: {e}")),
+        other => Err(format!("{key}: unexpected {other}")),
+    })
+}
+
+/// Latest masterchain block as seen by the provider.
+#[derive(Clone, Copy, Debug)]
+pub struct TonTip {
+    pub seqno: u64,
+    pub utime: u64,
+}
+
 #[derive(Deserialize, Debug)]
 struct BurnsResponse {
     #[serde(rename = "jetton_burns", default)]
-    burns: Vec<BurnItem>,
+    burns: Vec<serde_json::Value>,
 }
 
-#[derive(Deserialize, Debug)]
-struct BurnItem {
-    #[serde(rename = "transaction_hash")] tx_hash_b64: String,
-    #[serde(rename = "transaction_lt")]   transaction_lt: String,
-    #[serde(rename = "transaction_now")]  transaction_now: u64,
-    #[serde(rename = "jetton_master")]    jetton_master: String,
-    #[serde(rename = "owner")]            owner: String,
-    #[serde(rename = "jetton_wallet")]    jetton_wallet: String,
-    #[serde(default)]                     amount: String,
-    #[serde(default)]                     custom_payload: Option<String>,
+/// Send a toncenter request, recording its latency and status in the metrics.
+async fn send(req: reqwest::RequestBuilder, endpoint: &str) -> Result<reqwest::Response> {
+    let started = std::time::Instant::now();
+    let res = req.send().await;
+    let status = match &res {
+        Ok(r) => r.status().as_str().to_owned(),
+        Err(_) => "error".to_owned(),
+    };
+    METRICS.provider_request("toncenter", endpoint, &status, started.elapsed());
+    Ok(res?)
 }
 
-fn b64_to_vec(s: &str) -> Option<Vec<u8>> {
-    base64::engine::general_purpose::STANDARD.decode(s).ok()
-}
+/// Burns fetched per request once a route has a cursor.
+pub const BURN_PAGE: usize = 50;
 
-pub async fn poll_latest_burn() -> Result<Option<TonBurn>> {
-    let base = std::env::var("TON_API_BASE")
-        .unwrap_or_else(|_| "https://testnet.toncenter.com/api/v3".into());
-    let master = std::env::var("TON_WATCH_ADDR_B64")
-        .map_err(|_| anyhow::anyhow!("TON_WATCH_ADDR_B64 missing"))?;
-    let url = format!("{base}/jetton/burns?master={master}&limit=1&sort=desc");
+/// Burns of `master`, oldest first. With a cursor, up to [`BURN_PAGE`] burns
+/// with an lt above it, so nothing is skipped however long the route was not
+/// polled; without one (a new route), only the latest burn.
+#[tracing::instrument(skip_all, fields(master = %master))]
+pub async fn poll_burns(cfg: &Cfg, master: &TonAddress, after_lt: Option<u64>) -> Result<Vec<RawBurn>> {
+    let page = match after_lt {
+        Some(lt) => format!("start_lt={}&limit={BURN_PAGE}&sort=asc", lt + 1),
+        None => "limit=1&sort=desc".to_string(),
+    };
+    let url = format!("{}/jetton/burns?master={}&{page}", cfg.ton_api_base(), master.to_raw());
 
     let mut req = reqwest::Client::new().get(&url);
-    if let Ok(key) = std::env::var("TONCENTER_API_KEY") {
-        if !key.is_empty() {
-            req = req.header("X-API-KEY", key);
-        }
+    if let Some(key) = &cfg.ton_api_key {
+        req = req.header("X-API-KEY", key);
     }
 
     info!("GET {}", url);
-    let resp = req.send().await?;
+    let resp = send(req, "jetton/burns").await?;
     let status = resp.status();
+    let retry_after = resp
+        .headers()
+        .get(reqwest::header::RETRY_AFTER)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_owned);
     let text = resp.text().await?;
     info!(
         "status={} {} body[0..600]={}",
@@ -63,29 +108,46 @@ pub async fn poll_latest_burn() -> Result<Option<TonBurn>> {
     );
 
     if !status.is_success() {
-        return Ok(None);
+        return Err(RelayerError::from_status("toncenter", status, retry_after.as_deref(), &text));
     }
 
-    let parsed: BurnsResponse = serde_json::from_str(&text).context("parse burns json")?;
-    let it = match parsed.burns.into_iter().next() {
-        Some(x) => x,
-        None => return Ok(None),
-    };
+    let parsed: BurnsResponse = serde_json::from_str(&text)
+        .map_err(|e| RelayerError::Decode(format!("parse burns json: {e}")))?;
+    let burns: Vec<RawBurn> = parsed.burns.into_iter().map(RawBurn).collect();
+    for burn in &burns {
+        info!(
+            "TON watcher: forwarded burn tx {} amount {}",
+            burn.tx_hash_b64().unwrap_or("?"),
+            burn.str("amount").unwrap_or("?")
+        );
+    }
+    Ok(burns)
+}
 
-    let lt: u64 = it.transaction_lt.parse().unwrap_or(0);
-    let custom_payload = it.custom_payload.as_deref().and_then(b64_to_vec);
-
-    let burn = TonBurn {
-        tx_hash_b64: it.tx_hash_b64,
-        lt,
-        utime: it.transaction_now,
-        jetton_master_raw: it.jetton_master,
-        owner_raw: it.owner,
-        jetton_wallet_raw: it.jetton_wallet,
-        amount_raw: it.amount,
-        custom_payload,
-    };
+#[tracing::instrument(skip_all)]
+pub async fn masterchain_tip(cfg: &Cfg) -> Result<TonTip> {
+    let mut req = reqwest::Client::new().get(format!("{}/masterchainInfo", cfg.ton_api_base()));
+    if let Some(key) = &cfg.ton_api_key {
+        req = req.header("X-API-KEY", key);
+    }
+    let resp = send(req, "masterchainInfo").await?;
+    let status = resp.status();
+    let retry_after = resp
+        .headers()
+        .get(reqwest::header::RETRY_AFTER)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_owned);
+    let text = resp.text().await?;
+    if !status.is_success() {
+        return Err(RelayerError::from_status("toncenter", status, retry_after.as_deref(), &text));
+    }
 
-    info!("TON watcher: forwarded burn tx {} amount {}", burn.tx_hash_b64, burn.amount_raw);
-    Ok(Some(burn))
+    let v: serde_json::Value = serde_json::from_str(&text)?;
+    let last = v.get("last").ok_or_else(|| RelayerError::Decode("masterchainInfo: last missing".into()))?;
+    let field = |key| {
+        json_u64(last, key)
+            .unwrap_or_else(|| Err(format!("{key} missing")))
+            .map_err(|e| RelayerError::Decode(format!("masterchainInfo: {e}")))
+    };
+    Ok(TonTip { seqno: field("seqno")?, utime: field("gen_utime")? })
 }
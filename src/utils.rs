@@ -9,8 +9,25 @@ pub fn hex32(hexstr: &str) -> Result<[u8; 32]> {
 }
 
 pub fn now_ts() -> u64 {
-    (std::time::SystemTime::now()
+    std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)
         .unwrap()
-        .as_secs()) as u64
+        .as_secs()
+}
+
+/// Parse a duration such as `"500ms"`, `"5s"`, `"2m"` or `"1h"`.
+/// A bare number is taken as seconds.
+pub fn parse_duration(s: &str) -> Result<std::time::Duration, String> {
+    let s = s.trim();
+    let split = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    let (num, unit) = s.split_at(split);
+    let n: u64 = num.parse().map_err(|_| format!("invalid duration {s:?}"))?;
+    let d = match unit.trim() {
+        "ms" => std::time::Duration::from_millis(n),
+        "" | "s" => std::time::Duration::from_secs(n),
+        "m" => std::time::Duration::from_secs(n * 60),
+        "h" => std::time::Duration::from_secs(n * 3600),
+        u => return Err(format!("invalid duration unit {u:?} in {s:?} (use ms, s, m or h)")),
+    };
+    Ok(d)
 }
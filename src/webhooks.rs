@@ -0,0 +1,261 @@
+use futures::StreamExt;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use sqlx::SqlitePool;
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
+
+use crate::errors::{RelayerError, Result};
+use crate::events::{EventBus, EventRecord, RelayerEvent};
+use crate::metrics::METRICS;
+use crate::repo::{self, DueDelivery, NewDelivery};
+use crate::utils::now_ts;
+
+/// Cursor holding the last event id queued for delivery.
+const FANOUT_CURSOR: &str = "webhooks_last_event_id";
+const BATCH: i64 = 200;
+const CONCURRENCY: usize = 8;
+
+pub const SIGNATURE_HEADER: &str = "x-relayer-signature";
+
+#[derive(Debug, Clone)]
+pub struct DeliveryPolicy {
+    /// Attempts before a delivery is dead-lettered.
+    pub max_attempts: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    pub timeout: Duration,
+}
+
+impl DeliveryPolicy {
+    /// Delay after the `attempt`-th failure (1-based).
+    fn backoff(&self, attempt: u32) -> Duration {
+        let exp = attempt.saturating_sub(1).min(16);
+        self.initial_backoff.saturating_mul(1 << exp).min(self.max_backoff)
+    }
+}
+
+/// `t=<unix>,v1=<hex hmac-sha256(secret, "<t>.<body>")>`. The timestamp is
+/// signed too, so receivers can reject replays of old deliveries.
+pub fn signature(secret: &str, ts: u64, body: &str) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("hmac takes any key length");
+    mac.update(format!("{ts}.{body}").as_bytes());
+    format!("t={ts},v1={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// Random signing secret for a new webhook.
+pub fn new_secret() -> Result<String> {
+    let mut b = [0u8; 32];
+    getrandom::getrandom(&mut b).map_err(|e| RelayerError::Invariant(format!("no randomness: {e}")))?;
+    Ok(hex::encode(b))
+}
+
+/// Event types a webhook may subscribe to: comma-separated, empty for all.
+pub fn parse_types(types: &str) -> std::result::Result<String, String> {
+    let list: Vec<&str> = types.split(',').map(str::trim).filter(|t| !t.is_empty()).collect();
+    if let Some(t) = list.iter().find(|t| !RelayerEvent::NAMES.contains(t)) {
+        return Err(format!("unknown event type {t:?}, expected one of {:?}", RelayerEvent::NAMES));
+    }
+    Ok(list.join(","))
+}
+
+/// Delivery worker: queues every new event for each subscribed webhook,
+/// then sends whatever is due. Woken by the bus, with a timer for retries.
+pub async fn run(cfg: crate::config::Cfg, db: SqlitePool, bus: EventBus, shutdown: CancellationToken) -> Result<()> {
+    let policy = cfg.webhook_policy();
+    let client = reqwest::Client::builder()
+        .timeout(policy.timeout)
+        .build()
+        .map_err(|e| RelayerError::Invariant(format!("http client: {e}")))?;
+    let mut rx = bus.subscribe();
+
+    while !shutdown.is_cancelled() {
+        fan_out(&db).await?;
+        deliver_due(&db, &client, &policy).await?;
+        tokio::select! {
+            _ = rx.recv() => {}
+            _ = tokio::time::sleep(Duration::from_secs(1)) => {}
+            _ = shutdown.cancelled() => {}
+        }
+    }
+    tracing::info!("webhook delivery stopped");
+    Ok(())
+}
+
+/// Queue events past the fan-out cursor. On first start the cursor is set
+/// to the head of the log, so webhooks never receive history.
+async fn fan_out(db: &SqlitePool) -> Result<()> {
+    let cursor = match repo::get_cursor(db, FANOUT_CURSOR).await? {
+        Some(v) => v.parse::<i64>().map_err(|e| RelayerError::Invariant(format!("{FANOUT_CURSOR}: {e}")))?,
+        None => {
+            let head = repo::latest_event_id(db).await?.unwrap_or(0);
+            repo::set_cursor(db, FANOUT_CURSOR, &head.to_string()).await?;
+            head
+        }
+    };
+    let rows = repo::list_events_after(db, cursor, BATCH).await?;
+    let Some(last) = rows.last().map(|r| r.id) else {
+        return Ok(());
+    };
+    let hooks: Vec<_> = repo::list_webhooks(db).await?.into_iter().filter(|h| h.enabled).collect();
+
+    let mut deliveries = Vec::new();
+    for row in rows {
+        let event = match RelayerEvent::from_json(&row.data) {
+            Ok(e) => e,
+            Err(e) => {
+                tracing::warn!("webhooks: skipping event #{}: {e}", row.id);
+                continue;
+            }
+        };
+        let payload = EventRecord { id: row.id, event }.to_json();
+        for h in &hooks {
+            if h.types.is_empty() || h.types.split(',').any(|t| t == row.name) {
+                deliveries.push(NewDelivery {
+                    webhook_id: h.id,
+                    event_id: row.id,
+                    event_name: row.name.clone(),
+                    payload: payload.clone(),
+                });
+            }
+        }
+    }
+    repo::enqueue_webhook_deliveries(db, &deliveries, FANOUT_CURSOR, last).await
+}
+
+async fn deliver_due(db: &SqlitePool, client: &reqwest::Client, policy: &DeliveryPolicy) -> Result<()> {
+    let due = repo::list_due_deliveries(db, now_ts() as i64, BATCH).await?;
+    let results: Vec<Result<()>> = futures::stream::iter(due)
+        .map(|d| deliver(db, client, policy, d))
+        .buffer_unordered(CONCURRENCY)
+        .collect()
+        .await;
+    results.into_iter().collect()
+}
+
+#[tracing::instrument(skip_all, fields(webhook = d.webhook_id, event = d.event_id, attempt = d.attempts + 1))]
+async fn deliver(db: &SqlitePool, client: &reqwest::Client, policy: &DeliveryPolicy, d: DueDelivery) -> Result<()> {
+    let ts = now_ts();
+    let res = client
+        .post(&d.url)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .header(SIGNATURE_HEADER, signature(&d.secret, ts, &d.payload))
+        .header("x-relayer-event", &d.event_name)
+        .header("x-relayer-event-id", d.event_id.to_string())
+        .header("x-relayer-delivery", d.id.to_string())
+        .body(d.payload.clone())
+        .send()
+        .await;
+
+    let (error, status) = match res {
+        Ok(r) if r.status().is_success() => {
+            METRICS.webhook_deliveries.with_label_values(&["delivered"]).inc();
+            return repo::delete_delivery(db, d.id).await;
+        }
+        Ok(r) => (format!("receiver answered {}", r.status()), Some(r.status().as_u16() as i64)),
+        Err(e) => (e.to_string(), None),
+    };
+
+    let attempt = d.attempts as u32 + 1;
+    if attempt >= policy.max_attempts {
+        tracing::warn!("webhook {} gave up on event {} after {attempt} attempts: {error}", d.webhook_id, d.event_id);
+        METRICS.webhook_deliveries.with_label_values(&["dead"]).inc();
+        repo::dead_letter_delivery(db, d.id, &error, status).await
+    } else {
+        let delay = policy.backoff(attempt);
+        tracing::debug!("webhook {} event {} failed, retrying in {delay:?}: {error}", d.webhook_id, d.event_id);
+        METRICS.webhook_deliveries.with_label_values(&["retry"]).inc();
+        repo::retry_delivery(db, d.id, (ts + delay.as_secs()) as i64, &error, status).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::BurnEvent;
+    use axum::{http::HeaderMap, routing::post, Router};
+    use std::sync::{
+        atomic::{AtomicU16, Ordering},
+        Arc, Mutex,
+    };
+
+    type Received = Arc<Mutex<Vec<(HeaderMap, String)>>>;
+
+    /// Local receiver answering with whatever status `status` holds.
+    async fn receiver(status: Arc<AtomicU16>) -> (String, Received) {
+        let received: Received = Arc::default();
+        let r = received.clone();
+        let app = Router::new().route(
+            "/hook",
+            post(move |headers: HeaderMap, body: String| {
+                let (r, status) = (r.clone(), status.clone());
+                async move {
+                    r.lock().unwrap().push((headers, body));
+                    axum::http::StatusCode::from_u16(status.load(Ordering::SeqCst)).unwrap()
+                }
+            }),
+        );
+        let url = format!("http://{}/hook", crate::api::tests::listen(app).await);
+        (url, received)
+    }
+
+    fn burn(n: u64) -> RelayerEvent {
+        RelayerEvent::Burn(BurnEvent {
+            route: "USDT".into(),
+            src_tx: format!("tx{n}"),
+            lt: n,
+            utime: n,
+            owner: "0:AA".into(),
+            amount_raw: "1".into(),
+        })
+    }
+
+    #[tokio::test]
+    async fn delivers_signed_then_dead_letters_and_replays() {
+        let db = repo::tests::temp_db().await;
+        let bus = EventBus::new(db.clone(), 16);
+        let status = Arc::new(AtomicU16::new(200));
+        let (url, received) = receiver(status.clone()).await;
+        let hook = repo::insert_webhook(&db, &url, "s3cret", "burn").await.unwrap();
+        let policy = DeliveryPolicy {
+            max_attempts: 2,
+            initial_backoff: Duration::ZERO,
+            max_backoff: Duration::ZERO,
+            timeout: Duration::from_secs(5),
+        };
+        let client = reqwest::Client::new();
+
+        bus.emit(burn(0)).await; // before the cursor exists: never delivered
+        fan_out(&db).await.unwrap();
+        let id = bus.emit(burn(1)).await.unwrap();
+        fan_out(&db).await.unwrap();
+        deliver_due(&db, &client, &policy).await.unwrap();
+
+        {
+            let got = received.lock().unwrap();
+            assert_eq!(got.len(), 1);
+            let (headers, body) = &got[0];
+            assert_eq!(headers["x-relayer-event-id"], id.to_string().as_str());
+            let sig = headers[SIGNATURE_HEADER].to_str().unwrap();
+            let ts: u64 = sig.trim_start_matches("t=").split(',').next().unwrap().parse().unwrap();
+            assert_eq!(sig, signature("s3cret", ts, body));
+            assert!(body.starts_with(&format!(r#"{{"id":{id},"v":1,"type":"burn""#)), "{body}");
+        }
+        assert!(repo::list_due_deliveries(&db, i64::MAX, 10).await.unwrap().is_empty());
+
+        status.store(500, Ordering::SeqCst);
+        bus.emit(burn(2)).await;
+        fan_out(&db).await.unwrap();
+        deliver_due(&db, &client, &policy).await.unwrap();
+        deliver_due(&db, &client, &policy).await.unwrap();
+        let dead = repo::list_dead_letters(&db, Some(hook), 10).await.unwrap();
+        assert_eq!(dead.len(), 1);
+        assert_eq!((dead[0].attempts, dead[0].last_status), (2, Some(500)));
+
+        status.store(204, Ordering::SeqCst);
+        assert!(repo::replay_dead_letter(&db, dead[0].id).await.unwrap().is_some());
+        deliver_due(&db, &client, &policy).await.unwrap();
+        assert!(repo::list_dead_letters(&db, None, 10).await.unwrap().is_empty());
+        assert_eq!(received.lock().unwrap().len(), 4);
+    }
+}
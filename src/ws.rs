@@ -0,0 +1,297 @@
+use axum::extract::ws::{Message, WebSocket};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use std::time::Duration;
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::api::AppState;
+use crate::config::Cfg;
+use crate::events::RelayerEvent;
+use crate::metrics::{SubscriberGuard, METRICS};
+use crate::ton::address::TonAddress;
+
+/// A ping goes out this often; a client that has not sent anything (a pong
+/// included) by the next one is disconnected.
+const PING_INTERVAL: Duration = Duration::from_secs(20);
+/// A client that does not accept a frame within this long is too slow to keep.
+const SEND_TIMEOUT: Duration = Duration::from_secs(10);
+/// Events read from the log per batch while catching up.
+const REPLAY_BATCH: i64 = 500;
+const MAX_FILTER_VALUES: usize = 100;
+
+/// Which events a connection receives. Each non-empty list must match; an
+/// event that does not carry a field (a burn has no destination yet) does
+/// not match a filter on it.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct EventFilter {
+    pub types: Vec<String>,
+    /// TON owners, raw or user-friendly.
+    pub owners: Vec<String>,
+    /// Destination Solana accounts, base58.
+    pub dsts: Vec<String>,
+    pub routes: Vec<String>,
+}
+
+/// `/ws` query: the initial filter as comma-separated lists, and where to
+/// resume from.
+#[derive(Debug, Default, Deserialize, utoipa::IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct WsQ {
+    /// Event types, e.g. `burn,attestation`.
+    types: Option<String>,
+    /// TON owner addresses, any format.
+    owners: Option<String>,
+    /// Destination Solana accounts, base58.
+    dsts: Option<String>,
+    /// Route names.
+    routes: Option<String>,
+    pub last_event_id: Option<i64>,
+}
+
+impl WsQ {
+    pub fn filter(&self) -> EventFilter {
+        let list = |s: &Option<String>| -> Vec<String> {
+            s.as_deref()
+                .map(|s| s.split(',').map(str::trim).filter(|v| !v.is_empty()).map(String::from).collect())
+                .unwrap_or_default()
+        };
+        EventFilter {
+            types: list(&self.types),
+            owners: list(&self.owners),
+            dsts: list(&self.dsts),
+            routes: list(&self.routes),
+        }
+    }
+}
+
+impl EventFilter {
+    /// Check every value and rewrite addresses to the form events carry.
+    pub fn normalize(mut self, cfg: &Cfg) -> Result<Self, String> {
+        for (name, list) in [("types", &self.types), ("owners", &self.owners), ("dsts", &self.dsts), ("routes", &self.routes)] {
+            if list.len() > MAX_FILTER_VALUES {
+                return Err(format!("{name}: at most {MAX_FILTER_VALUES} values"));
+            }
+        }
+        if let Some(t) = self.types.iter().find(|t| !RelayerEvent::NAMES.contains(&t.as_str())) {
+            return Err(format!("types: unknown event type {t:?}, expected one of {:?}", RelayerEvent::NAMES));
+        }
+        if let Some(r) = self.routes.iter().find(|r| !cfg.routes.iter().any(|route| &route.name == *r)) {
+            return Err(format!("routes: no route named {r:?}"));
+        }
+        self.owners = self
+            .owners
+            .iter()
+            .map(|o| o.parse::<TonAddress>().map(|a| a.to_raw()).map_err(|e| format!("owners: {o:?}: {e}")))
+            .collect::<Result<_, _>>()?;
+        self.dsts = self
+            .dsts
+            .iter()
+            .map(|d| {
+                solana_sdk::pubkey::Pubkey::from_str(d)
+                    .map(|p| p.to_string())
+                    .map_err(|e| format!("dsts: {d:?}: {e}"))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(self)
+    }
+
+    pub fn matches(&self, ev: &RelayerEvent) -> bool {
+        fn any(list: &[String], value: Option<&str>) -> bool {
+            list.is_empty() || value.is_some_and(|v| list.iter().any(|x| x == v))
+        }
+        // Compared in raw form, like the normalized filter, whatever form
+        // the event carries.
+        let owner = || ev.owner().map(|o| o.parse::<TonAddress>().map_or_else(|_| o.to_string(), TonAddress::to_raw));
+        any(&self.types, Some(ev.name()))
+            && (self.owners.is_empty() || any(&self.owners, owner().as_deref()))
+            && any(&self.dsts, ev.dst())
+            && any(&self.routes, ev.route())
+    }
+}
+
+/// Messages a client may send. Anything else gets an `error` reply.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+enum ClientMsg {
+    /// Replace the connection's filter.
+    Subscribe(EventFilter),
+}
+
+fn control(value: serde_json::Value) -> Message {
+    Message::Text(value.to_string())
+}
+
+/// Send one frame, giving up on a client that does not take it in time.
+async fn send(socket: &mut WebSocket, msg: Message) -> bool {
+    matches!(tokio::time::timeout(SEND_TIMEOUT, socket.send(msg)).await, Ok(Ok(())))
+}
+
+/// One `/ws` connection. Events are sent as text frames in their
+/// [`crate::events::EventRecord::to_json`] form; control frames (`subscribed`,
+/// `lag`, `error`, `shutdown`) have a `type` but no `id`.
+///
+/// Backpressure: frames are written one at a time, so a slow client only
+/// slows its own receiver. If that receiver falls behind the bus it gets a
+/// `lag` frame and is caught up from the event log at its own pace; a client
+/// that stops reading is dropped after `SEND_TIMEOUT`.
+pub async fn session(mut socket: WebSocket, st: AppState, mut filter: EventFilter, resume: Option<i64>) {
+    let _subscriber = SubscriberGuard::acquire(&METRICS.ws_subscribers);
+    let mut rx = st.bus.subscribe();
+    let mut last = resume;
+    let mut ping = tokio::time::interval_at(tokio::time::Instant::now() + PING_INTERVAL, PING_INTERVAL);
+    let mut heard_from_client = true;
+
+    if !send(&mut socket, control(serde_json::json!({ "type": "subscribed", "filter": filter }))).await {
+        return;
+    }
+    if let Some(after) = resume {
+        if let Ok(Some(oldest)) = st.bus.oldest_id().await {
+            if oldest > after + 1 {
+                let lag = serde_json::json!({ "type": "lag", "reason": "expired", "requested_after": after, "oldest_id": oldest });
+                if !send(&mut socket, control(lag)).await {
+                    return;
+                }
+            }
+        }
+    }
+
+    'session: loop {
+        if let Some(mut after) = last {
+            loop {
+                let batch = match st.bus.replay(after, REPLAY_BATCH).await {
+                    Ok(b) => b,
+                    Err(e) => {
+                        tracing::warn!("ws: replay after {after} failed: {e}");
+                        break 'session;
+                    }
+                };
+                for rec in &batch {
+                    after = rec.id;
+                    if filter.matches(&rec.event) && !send(&mut socket, Message::Text(rec.to_json())).await {
+                        break 'session;
+                    }
+                }
+                if (batch.len() as i64) < REPLAY_BATCH {
+                    break;
+                }
+            }
+            last = Some(after);
+        }
+
+        loop {
+            tokio::select! {
+                msg = rx.recv() => match msg {
+                    Ok(rec) => {
+                        if last.is_some_and(|l| rec.id <= l) {
+                            continue;
+                        }
+                        last = Some(rec.id);
+                        if filter.matches(&rec.event) && !send(&mut socket, Message::Text(rec.to_json())).await {
+                            break 'session;
+                        }
+                    }
+                    Err(RecvError::Closed) => break 'session,
+                    Err(RecvError::Lagged(missed)) => {
+                        let lag = serde_json::json!({
+                            "type": "lag",
+                            "reason": "lagged",
+                            "missed": missed,
+                            "replayed": last.is_some(),
+                        });
+                        if !send(&mut socket, control(lag)).await {
+                            break 'session;
+                        }
+                        continue 'session;
+                    }
+                },
+                frame = socket.recv() => {
+                    heard_from_client = true;
+                    let reply = match frame {
+                        Some(Ok(Message::Text(text))) => match serde_json::from_str::<ClientMsg>(&text) {
+                            Ok(ClientMsg::Subscribe(f)) => match f.normalize(&st.cfg) {
+                                Ok(f) => {
+                                    filter = f;
+                                    serde_json::json!({ "type": "subscribed", "filter": filter })
+                                }
+                                Err(e) => serde_json::json!({ "type": "error", "message": e }),
+                            },
+                            Err(e) => serde_json::json!({ "type": "error", "message": format!("bad message: {e}") }),
+                        },
+                        // Pings are answered by the protocol layer; binary frames are ignored.
+                        Some(Ok(Message::Ping(_) | Message::Pong(_) | Message::Binary(_))) => continue,
+                        Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break 'session,
+                    };
+                    if !send(&mut socket, control(reply)).await {
+                        break 'session;
+                    }
+                }
+                _ = ping.tick() => {
+                    if !heard_from_client {
+                        tracing::debug!("ws: client missed a ping, disconnecting");
+                        break 'session;
+                    }
+                    heard_from_client = false;
+                    if !send(&mut socket, Message::Ping(Vec::new())).await {
+                        break 'session;
+                    }
+                }
+                _ = st.shutdown.cancelled() => {
+                    let _ = send(&mut socket, control(serde_json::json!({ "type": "shutdown" }))).await;
+                    let _ = send(&mut socket, Message::Close(None)).await;
+                    break 'session;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::{BurnEvent, QuarantineEvent};
+
+    fn burn(owner: &str) -> RelayerEvent {
+        RelayerEvent::Burn(BurnEvent {
+            route: "USDT".into(),
+            src_tx: "tx".into(),
+            lt: 1,
+            utime: 1,
+            owner: owner.into(),
+            amount_raw: "1".into(),
+        })
+    }
+
+    #[test]
+    fn filter_lists_must_all_match() {
+        let owner = "0:83DFD552E63729B472FCBCC8C45EBCC6691702558B68EC7527E1BA403A0F31A8";
+        let quarantine = RelayerEvent::Quarantine(QuarantineEvent {
+            route: "USDT".into(),
+            key: "k".into(),
+            code: "precision".into(),
+            reason: "r".into(),
+            releasable: true,
+        });
+
+        assert!(EventFilter::default().matches(&burn(owner)));
+        let f = EventFilter { types: vec!["burn".into()], routes: vec!["USDT".into()], ..Default::default() };
+        assert!(f.matches(&burn(owner)) && !f.matches(&quarantine));
+
+        let f = EventFilter { owners: vec![owner.into()], ..Default::default() };
+        assert!(f.matches(&burn(owner)) && !f.matches(&burn("0:AA")));
+        // Events logged before owners were stored canonically still match.
+        assert!(f.matches(&burn(&owner.to_lowercase())));
+        assert!(f.matches(&burn("EQCD39VS5jcptHL8vMjEXrzGaRcCVYto7HUn4bpAOg8xqB2N")));
+        // Quarantine events carry no owner, so an owner filter excludes them.
+        assert!(!f.matches(&quarantine));
+        let f = EventFilter { dsts: vec!["x".into()], ..Default::default() };
+        assert!(!f.matches(&burn(owner)));
+    }
+
+    #[test]
+    fn query_lists_are_split_and_trimmed() {
+        let q = WsQ { types: Some("burn, attestation,".into()), ..Default::default() };
+        assert_eq!(q.filter().types, vec!["burn", "attestation"]);
+        assert!(q.filter().owners.is_empty());
+    }
+}